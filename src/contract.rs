@@ -1,351 +1,5399 @@
 use cosmwasm_std::{
-    entry_point, BankMsg, SubMsg, Coin, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128, StdError
+    entry_point, Api, BankMsg, Coin, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env, Event, MessageInfo, Order, QueryRequest,
+    Response, StdResult, Storage, Timestamp, Uint128, Uint256, StdError, WasmMsg
 };
-use cosmwasm_std::to_binary;
+use cosmwasm_std::{from_binary, to_binary};
+use std::convert::TryFrom;
+use std::str::FromStr;
 use cw2::set_contract_version;
+use cw3::{
+    ProposalResponse, Status, Vote, VoteInfo, VoteListResponse, VoteResponse, VoterDetail,
+    VoterListResponse, VoterResponse,
+};
+use coreum_wasm_sdk::{assetft, assetnft, nft};
+use coreum_wasm_sdk::core::{CoreumMsg, CoreumQueries};
+use cw20::{Cw20Coin, Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw4::{Cw4Contract, MemberChangedHookMsg};
+use cw_utils::{Expiration, ThresholdResponse};
+use cw_storage_plus::{Bound, Map};
 use cosmwasm_std::{ Addr};
+use serde::Serialize;
 use thiserror::Error;
-use serde::{Deserialize, Serialize};
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Proposal, Member, PROPOSAL_COUNT, PROPOSALS, MEMBERS};
+use crate::events;
+use crate::msg::{
+    ExecuteMsg, FundingApplicationInit, GovernanceTokenIssue, GovernanceTokenResponse, InstantiateMsg, MatchingResponse,
+    CertificateListResponse, Cw20BalancesResponse, MemberInit, MembershipNftResponse, NftClassInit, PayoutSchedule, ProposalListResponse, QueryMsg, RecurringResponse, RoundApplicationInit,
+};
+use crate::state::{
+    ApplicationStatus, Ballot, Config, ContentLimits, Contribution, Delinquency, FundingApplication, GrantApplication, GrantRound, Milestone,
+    MilestoneStatus, Payout, Proposal, ProposalAction, ProposalStatus, Member, QuadraticRound, RecipientListUpdate,
+    Review, RoundApplication, RoundBallot, TallyMethod, TokenAdminMsg, PROPOSAL_COUNT, PROPOSALS, MEMBERS, ROUNDS, ROUND_BALLOTS,
+    VetoCouncil, VetoCouncilInit, ROUND_COUNT, CONTRIBUTIONS, QUADRATIC_ROUNDS, QUADRATIC_ROUND_COUNT, RESERVED, APPLICATIONS, APPLICATION_COUNT,
+    BALLOTS, CERTIFICATES, CLAIMS, CONFIG, CW20_BALANCES, GrantCertificate, MEMBERSHIP_NFTS, MEMBERSHIP_NFT_COUNT, MembershipNft, STAKES, StakingMode, TOTAL_WEIGHT, REVIEWERS, REVIEWS, DELINQUENTS, RECIPIENT_ALLOWLIST, RECIPIENT_BLOCKLIST,
+};
 
-const CONTRACT_NAME: &str = "workshop-dao";
+const CONTRACT_NAME: &str = "grant-dao";
 const CONTRACT_VERSION: &str = "0.1.0";
 
+const GRANT_DENOM: &str = "udevcore"; // default of `Config::grant_denom`
+const VOTING_PERIOD: u64 = 604800; // 7 days in seconds
+const MAX_REVIEW_SCORE: u8 = 10;
+const REVIEW_PERIOD: u64 = 604800; // 7 days in seconds
+const ADDRESS_PREFIXES: [&str; 3] = ["core", "testcore", "devcore"];
+const MAX_PAUSE_PERIOD: u64 = 2592000; // 30 days in seconds
+// Founding members are recorded as of height 0, so proposals made in the instantiation block count them
+const FOUNDING_HEIGHT: u64 = 0;
+// Page sizes of the cw3 list queries
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+const URL_SCHEMES: [&str; 3] = ["https://", "http://", "ipfs://"];
+
 #[derive(Error, Debug)]
 pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
     #[error("Unauthorized")]
     Unauthorized {},
-    #[error("Invalid input")]
+    #[error("Not A Member: {address}")]
+    NotMember { address: String },
+    #[error("Invalid Input: {0}")]
     InvalidInput(String),
+    #[error("Proposal {id} Not Found")]
+    ProposalNotFound { id: u64 },
+    #[error("Grant Round {id} Not Found")]
+    RoundNotFound { id: u64 },
+    #[error("Quadratic Round {id} Not Found")]
+    QuadraticRoundNotFound { id: u64 },
+    #[error("Application {id} Not Found")]
+    ApplicationNotFound { id: u64 },
+    #[error("Proposal Not Passed")]
+    NotPassed {},
+    #[error("Insufficient Treasury: {available} available, {required} required")]
+    InsufficientTreasury { available: Uint128, required: Uint128 },
     #[error("Already Executed")]
     AlreadyExecuted {},
+    #[error("Already Voted")]
+    AlreadyVoted {},
+    #[error("Voting Closed")]
+    VotingClosed {},
+    #[error("Voting Open")]
+    VotingOpen {},
+    #[error("Proposal In Review")]
+    InReview {},
+    #[error("Recipient Is Delinquent")]
+    DelinquentRecipient {},
+    #[error("Grant Clawed Back")]
+    ClawedBack {},
+    #[error("Recipient Is Blocked")]
+    RecipientBlocked {},
+    #[error("Recipient Is Not Allow-listed")]
+    RecipientNotAllowed {},
+    #[error("Contract Paused Until {until}")]
+    Paused { until: u64 },
+    #[error("Proposal Timelocked Until {until}")]
+    Timelocked { until: u64 },
+    #[error("Proposal Vetoed")]
+    Vetoed {},
+    #[error("Nothing To Claim")]
+    NothingToClaim {},
+    #[error("Treasury Frozen: {spendable} spendable, {required} required")]
+    TreasuryFrozen { spendable: Uint128, required: Uint128 },
+    #[error("Recipient {recipient} Can Only Receive {allowed} More Of The Grant Token")]
+    WhitelistLimit { recipient: String, allowed: Uint128 },
 }
 
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut,
-    _env: Env,
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response<CoreumMsg>, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     // Initialize proposal count with 0
     PROPOSAL_COUNT.save(deps.storage, &0u64)?;
+    ROUND_COUNT.save(deps.storage, &0u64)?;
+    QUADRATIC_ROUND_COUNT.save(deps.storage, &0u64)?;
+    RESERVED.save(deps.storage, &Uint128::zero())?;
+    APPLICATION_COUNT.save(deps.storage, &0u64)?;
+
+    let compliance = msg.compliance.map(|address| validate_address(deps.api, &address)).transpose()?;
+    let content_limits = msg.content_limits.unwrap_or_default();
+    validate_content_limits(&content_limits)?;
+    let guardian = msg.guardian.map(|address| validate_address(deps.api, &address)).transpose()?;
+    let timelock = msg.timelock.unwrap_or(0);
+    let quorum = msg.quorum.unwrap_or_default();
+    if quorum > Decimal::one() {
+        return Err(ContractError::InvalidInput("Quorum cannot exceed the total weight".to_string()));
+    }
+    let veto_council = msg.veto_council.map(|council| validate_veto_council(deps.api, &council, timelock)).transpose()?;
+    let group = msg.group.map(|address| validate_group(deps.as_ref(), &address)).transpose()?;
+    let governance_token = msg.governance_token
+        .map(|denom| validate_smart_token(deps.as_ref(), denom))
+        .transpose()?;
+    if group.is_some() && governance_token.is_some() {
+        return Err(ContractError::InvalidInput("Voting weight comes from either a group or a token, not both".to_string()));
+    }
+    let issue = msg.issue_governance_token;
+    if let Some(issue) = &issue {
+        if group.is_some() || governance_token.is_some() {
+            return Err(ContractError::InvalidInput(
+                "An issued governance token replaces the group or existing token".to_string(),
+            ));
+        }
+        validate_token_issue(issue)?;
+    }
+    let staking_mode = msg.staking_mode.unwrap_or_default();
+    if staking_mode == StakingMode::Supplement && governance_token.is_none() && issue.is_none() {
+        return Err(ContractError::InvalidInput("Supplementing weights needs a governance token to stake".to_string()));
+    }
+    // Coreum derives the denom of an issued token from its subunit and issuer
+    let governance_token = match &issue {
+        Some(issue) => Some(format!("{}-{}", issue.subunit, env.contract.address).to_lowercase()),
+        None => governance_token,
+    };
+    let grant_denom = match msg.grant_denom {
+        Some(denom) if is_smart_token(&denom) => validate_smart_token(deps.as_ref(), denom)?,
+        Some(denom) => denom,
+        None => GRANT_DENOM.to_string(),
+    };
+    // Staked tokens sit in the contract balance and would be paid out as treasury
+    if governance_token.as_deref() == Some(grant_denom.as_str()) {
+        return Err(ContractError::InvalidInput("The governance token cannot be the grant denom".to_string()));
+    }
+    let cw20_tokens = msg.cw20_tokens.unwrap_or_default().iter()
+        .map(|token| validate_address(deps.api, token))
+        .collect::<Result<Vec<_>, _>>()?;
+    let membership_nft = msg.membership_nft;
+    if membership_nft.is_some() && !has_member_list(group.is_some(), governance_token.is_some(), &staking_mode) {
+        return Err(ContractError::InvalidInput("Membership NFTs need the built-in member list".to_string()));
+    }
+    let grant_certificates = msg.grant_certificates;
+    if let (Some(membership), Some(certificates)) = (&membership_nft, &grant_certificates) {
+        if membership.symbol.eq_ignore_ascii_case(&certificates.symbol) {
+            return Err(ContractError::InvalidInput("Membership NFTs and certificates need different symbols".to_string()));
+        }
+    }
+    let membership_nft_class = membership_nft.as_ref().map(|class| nft_class_id(&env, class));
+    let certificate_class = grant_certificates.as_ref().map(|class| nft_class_id(&env, class));
+    CONFIG.save(deps.storage, &Config {
+        application_fee: msg.application_fee.unwrap_or_default(),
+        application_period: msg.application_period.unwrap_or(VOTING_PERIOD),
+        burn_expired_fees: msg.burn_expired_fees.unwrap_or(false),
+        min_reviews: msg.min_reviews.unwrap_or(0),
+        allowlist_enabled: msg.allowlist_enabled.unwrap_or(false),
+        compliance,
+        content_limits,
+        guardian,
+        paused_until: None,
+        timelock,
+        quorum,
+        veto_council,
+        group: group.clone(),
+        governance_token: governance_token.clone(),
+        staking_mode: staking_mode.clone(),
+        unbonding_period: msg.unbonding_period.unwrap_or(VOTING_PERIOD),
+        membership_nft_class: membership_nft_class.clone(),
+        certificate_class,
+        grant_denom,
+        cw20_tokens,
+    })?;
+
+    for reviewer in msg.reviewers.unwrap_or_default() {
+        let reviewer = validate_address(deps.api, &reviewer)?;
+        REVIEWERS.save(deps.storage, reviewer.as_str(), &reviewer)?;
+    }
+
+
+    let members = validate_members(deps.api, msg.members)?;
+    let mut res = Response::new();
+    if let Some(class) = membership_nft {
+        res = res.add_message(issue_nft_class(class, vec![assetnft::BURNING, assetnft::DISABLE_SENDING]));
+    }
+    if let Some(class) = grant_certificates {
+        res = res.add_message(issue_nft_class(class, vec![assetnft::DISABLE_SENDING]));
+    }
+    let issuing = issue.is_some();
+    if let Some(issue) = issue {
+        // Founding members receive the supply by weight and stake it to vote
+        if members.is_empty() {
+            return Err(ContractError::InvalidInput("The governance token needs founding members to hold it".to_string()));
+        }
+        let denom = governance_token.clone().unwrap_or_default();
+        let total = members.iter().map(|member| member.weight).sum::<Uint128>();
+        let whitelisting = issue.features.contains(&assetft::WHITELISTING);
+        res = res
+            .add_message(CoreumMsg::AssetFT(assetft::Msg::Issue {
+                symbol: issue.symbol,
+                subunit: issue.subunit,
+                precision: issue.precision,
+                initial_amount: issue.supply,
+                description: issue.description,
+                features: Some(issue.features),
+                burn_rate: None,
+                send_commission_rate: None,
+            }))
+            .add_event(events::config_changed("governance_token", &denom)?);
+        // Rounding leftovers stay in the treasury. A whitelisted token only reaches members whose
+        // limit covers their share, which the issuer sets first.
+        for member in &members {
+            let amount = issue.supply.multiply_ratio(member.weight, total);
+            if !amount.is_zero() {
+                if whitelisting {
+                    res = res.add_message(CoreumMsg::AssetFT(assetft::Msg::SetWhitelistedLimit {
+                        account: member.address.to_string(),
+                        coin: Coin { denom: denom.clone(), amount },
+                    }));
+                }
+                res = res.add_message(BankMsg::Send {
+                    to_address: member.address.to_string(),
+                    amount: vec![Coin { denom: denom.clone(), amount }],
+                });
+            }
+        }
+    }
+    if let Some(group) = group {
+        if !members.is_empty() {
+            return Err(ContractError::InvalidInput("Members are managed by the group, leave the member list empty".to_string()));
+        }
+        let total = Cw4Contract::new(group).total_weight(&deps.querier.into_empty())?;
+        TOTAL_WEIGHT.save(deps.storage, &Uint128::from(total), FOUNDING_HEIGHT)?;
+        return Ok(res);
+    }
+    if governance_token.is_some() && staking_mode == StakingMode::Replace {
+        // Founding members of an issued token hold it instead of a weight
+        if !members.is_empty() && !issuing {
+            return Err(ContractError::InvalidInput("Token holders become members by staking, leave the member list empty".to_string()));
+        }
+        // Nothing is staked yet
+        TOTAL_WEIGHT.save(deps.storage, &Uint128::zero(), FOUNDING_HEIGHT)?;
+        return Ok(res);
+    }
+
+    let mut total = Uint128::zero();
+    for member in members {
+        MEMBERS.save(deps.storage, member.address.as_str(), &member, FOUNDING_HEIGHT)?;
+        total += member.weight;
+        res = res.add_event(events::member_changed(&member.address, member.weight));
+        if let Some(class_id) = &membership_nft_class {
+            res = res.add_messages(mint_membership_nft(deps.storage, class_id, &member, "founder")?);
+        }
+    }
+    TOTAL_WEIGHT.save(deps.storage, &total, FOUNDING_HEIGHT)?;
+
+    Ok(res)
+}
+
+/// Whether members are kept in `MEMBERS`, rather than in a group or as stakes alone
+fn has_member_list(group: bool, governance_token: bool, staking_mode: &StakingMode) -> bool {
+    !group && (!governance_token || *staking_mode == StakingMode::Supplement)
+}
+
+/// Like denoms, AssetNFT class ids are derived from the symbol and issuer
+fn nft_class_id(env: &Env, class: &NftClassInit) -> String {
+    format!("{}-{}", class.symbol, env.contract.address).to_lowercase()
+}
+
+fn issue_nft_class(class: NftClassInit, features: Vec<u32>) -> CoreumMsg {
+    CoreumMsg::AssetNFT(assetnft::Msg::IssueClass {
+        name: class.name,
+        symbol: class.symbol,
+        description: class.description,
+        uri: class.uri,
+        uri_hash: None,
+        data: None,
+        features: Some(features),
+        royalty_rate: None,
+    })
+}
+
+/// Mints an NFT to the contract, its issuer, and sends it on to `receiver`. The classes
+/// disable sending, so the NFT stays with the receiver.
+fn mint_nft<T: Serialize>(class_id: &str, id: &str, data: &T, receiver: &Addr) -> StdResult<Vec<CosmosMsg<CoreumMsg>>> {
+    Ok(vec![
+        CoreumMsg::AssetNFT(assetnft::Msg::Mint {
+            class_id: class_id.to_string(),
+            id: id.to_string(),
+            uri: None,
+            uri_hash: None,
+            data: Some(to_binary(data)?),
+        })
+        .into(),
+        CoreumMsg::NFT(nft::Msg::Send {
+            class_id: class_id.to_string(),
+            id: id.to_string(),
+            receiver: receiver.to_string(),
+        })
+        .into(),
+    ])
+}
+
+fn mint_membership_nft(
+    storage: &mut dyn Storage,
+    class_id: &str,
+    member: &Member,
+    role: &str,
+) -> StdResult<Vec<CosmosMsg<CoreumMsg>>> {
+    let count = MEMBERSHIP_NFT_COUNT.may_load(storage)?.unwrap_or_default();
+    MEMBERSHIP_NFT_COUNT.save(storage, &(count + 1))?;
+
+    let nft = MembershipNft { id: format!("member-{}", count), role: role.to_string(), weight: member.weight };
+    MEMBERSHIP_NFTS.save(storage, member.address.as_str(), &nft)?;
+    mint_nft(class_id, &nft.id, &nft, &member.address)
+}
+
+/// Mints the completion certificate of a grant that paid out `amount` in total, if the DAO
+/// issues certificates
+fn mint_certificate(
+    storage: &mut dyn Storage,
+    env: &Env,
+    proposal: &Proposal,
+    amount: Uint128,
+) -> StdResult<Vec<CosmosMsg<CoreumMsg>>> {
+    let class_id = match CONFIG.load(storage)?.certificate_class {
+        Some(class_id) => class_id,
+        None => return Ok(vec![]),
+    };
+    let certificate = GrantCertificate {
+        id: format!("grant-{}", proposal.id),
+        proposal_id: proposal.id,
+        recipient: proposal.recipient.clone(),
+        amount,
+        completed_at: env.block.time.seconds(),
+    };
+    let proposal_key = proposal.id.to_string();
+    CERTIFICATES.save(storage, (proposal.recipient.as_str(), &proposal_key), &certificate)?;
+    mint_nft(&class_id, &certificate.id, &certificate, &proposal.recipient)
+}
+
+/// Burns the NFT of a removed member, returns the burn message if the member held one
+fn burn_membership_nft(
+    storage: &mut dyn Storage,
+    class_id: &str,
+    address: &Addr,
+) -> StdResult<Option<CosmosMsg<CoreumMsg>>> {
+    let nft = match MEMBERSHIP_NFTS.may_load(storage, address.as_str())? {
+        Some(nft) => nft,
+        None => return Ok(None),
+    };
+    MEMBERSHIP_NFTS.remove(storage, address.as_str());
+    Ok(Some(CoreumMsg::AssetNFT(assetnft::Msg::Burn { class_id: class_id.to_string(), id: nft.id }).into()))
+}
+
+fn validate_members(api: &dyn Api, members: Vec<MemberInit>) -> Result<Vec<Member>, ContractError> {
+    let mut validated: Vec<Member> = vec![];
+    for member in members {
+        let address = validate_address(api, &member.address)?;
+        if member.weight.is_zero() {
+            return Err(ContractError::InvalidInput(format!("Member {} has zero weight", address)));
+        }
+        if validated.iter().any(|other| other.address == address) {
+            return Err(ContractError::InvalidInput(format!("Duplicate member {}", address)));
+        }
+        validated.push(Member { address, weight: member.weight });
+    }
+    Ok(validated)
+}
+
+/// Catches mistakes before the issue fee is spent, the chain checks the symbol and subunit format
+fn validate_token_issue(issue: &GovernanceTokenIssue) -> Result<(), ContractError> {
+    if issue.symbol.trim().is_empty() || issue.subunit.trim().is_empty() {
+        return Err(ContractError::InvalidInput("The governance token needs a symbol and subunit".to_string()));
+    }
+    if issue.supply.is_zero() {
+        return Err(ContractError::InvalidInput("The governance token needs a supply".to_string()));
+    }
+    for (index, feature) in issue.features.iter().enumerate() {
+        if *feature > assetft::IBC {
+            return Err(ContractError::InvalidInput(format!("Unknown token feature {}", feature)));
+        }
+        if issue.features[..index].contains(feature) {
+            return Err(ContractError::InvalidInput(format!("Duplicate token feature {}", feature)));
+        }
+    }
+    Ok(())
+}
+
+/// Validates a user supplied address and checks it carries one of the Coreum bech32 prefixes
+fn validate_address(api: &dyn Api, address: &str) -> Result<Addr, ContractError> {
+    let addr = api.addr_validate(address)
+        .map_err(|err| ContractError::InvalidInput(format!("Invalid address {}: {}", address, err)))?;
+
+    match address.rsplit_once('1') {
+        Some((prefix, data)) if ADDRESS_PREFIXES.contains(&prefix) && !data.is_empty() => Ok(addr),
+        _ => Err(ContractError::InvalidInput(format!("{} is not a Coreum address", address))),
+    }
+}
+
+fn validate_content_limits(limits: &ContentLimits) -> Result<(), ContractError> {
+    if limits.min_title_length > limits.max_title_length
+        || limits.min_description_length > limits.max_description_length
+    {
+        return Err(ContractError::InvalidInput("Minimum lengths cannot exceed maximum lengths".to_string()));
+    }
+    if limits.max_title_length == 0 {
+        return Err(ContractError::InvalidInput("Titles must be allowed at least one character".to_string()));
+    }
+    Ok(())
+}
+
+fn validate_length(field: &str, text: &str, min: u32, max: u32) -> Result<(), ContractError> {
+    let length = text.chars().count();
+    if length < min as usize {
+        return Err(ContractError::InvalidInput(format!(
+            "{} is {} characters, the minimum is {}", field, length, min
+        )));
+    }
+    if length > max as usize {
+        return Err(ContractError::InvalidInput(format!(
+            "{} is {} characters, the maximum is {}", field, length, max
+        )));
+    }
+    Ok(())
+}
+
+/// Checks a title and description against the configured limits
+fn validate_content(limits: &ContentLimits, title: &str, description: &str) -> Result<(), ContractError> {
+    if title.trim().is_empty() {
+        return Err(ContractError::InvalidInput("Title cannot be blank".to_string()));
+    }
+    validate_length("Title", title, limits.min_title_length, limits.max_title_length)?;
+    validate_length("Description", description, limits.min_description_length, limits.max_description_length)
+}
+
+fn validate_url(limits: &ContentLimits, url: &str) -> Result<(), ContractError> {
+    validate_length("URL", url, 1, limits.max_url_length)?;
+    let rest = URL_SCHEMES.iter().find_map(|scheme| url.strip_prefix(scheme));
+    match rest {
+        Some(rest) if !rest.is_empty() && !rest.chars().any(|c| c.is_whitespace() || c.is_control()) => Ok(()),
+        _ => Err(ContractError::InvalidInput(format!(
+            "URL must be an http(s) or ipfs link without whitespace, got {}", url
+        ))),
+    }
+}
+
+/// Checks a hex encoded sha256 digest and returns it in lowercase
+fn validate_content_hash(hash: &str) -> Result<String, ContractError> {
+    if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ContractError::InvalidInput(format!(
+            "Content hash must be a sha256 digest of 64 hex characters, got {}", hash
+        )));
+    }
+    Ok(hash.to_ascii_lowercase())
+}
+
+/// Group contracts are checked by querying them rather than by their address prefix
+fn validate_group(deps: Deps<CoreumQueries>, address: &str) -> Result<Addr, ContractError> {
+    let group = deps.api.addr_validate(address)?;
+    Cw4Contract::new(group.clone()).total_weight(&deps.querier.into_empty())
+        .map_err(|_| ContractError::InvalidInput(format!("{} is not a cw4 group", address)))?;
+    Ok(group)
+}
+
+/// Checks that the DAO issued the token, which makes it the admin, and that the token has the
+/// feature the operation needs
+fn validate_token_admin(deps: Deps<CoreumQueries>, env: &Env, msg: &TokenAdminMsg) -> Result<(), ContractError> {
+    let denom = msg.denom();
+    let query = QueryRequest::Custom(CoreumQueries::AssetFT(assetft::Query::Token { denom: denom.to_string() }));
+    let token = deps.querier.query::<assetft::TokenResponse>(&query)
+        .map_err(|_| ContractError::InvalidInput(format!("{} is not a Coreum smart token", denom)))?
+        .token;
+    if token.issuer != env.contract.address {
+        return Err(ContractError::InvalidInput(format!("The DAO does not administer {}", denom)));
+    }
+
+    let (feature, name) = match msg {
+        TokenAdminMsg::Mint { .. } => (Some(assetft::MINTING), "minting"),
+        // The issuer can always burn
+        TokenAdminMsg::Burn { .. } => (None, "burning"),
+        TokenAdminMsg::Freeze { .. }
+        | TokenAdminMsg::Unfreeze { .. }
+        | TokenAdminMsg::GloballyFreeze { .. }
+        | TokenAdminMsg::GloballyUnfreeze { .. } => (Some(assetft::FREEZING), "freezing"),
+        TokenAdminMsg::SetWhitelistedLimit { .. } => (Some(assetft::WHITELISTING), "whitelisting"),
+    };
+    if let Some(feature) = feature {
+        if !token.features.unwrap_or_default().contains(&feature) {
+            return Err(ContractError::InvalidInput(format!("{} does not have the {} feature", denom, name)));
+        }
+    }
 
+    match msg {
+        TokenAdminMsg::Mint { coin } | TokenAdminMsg::Burn { coin } if coin.amount.is_zero() => {
+            return Err(ContractError::InvalidInput("Amount must be greater than zero".to_string()));
+        }
+        TokenAdminMsg::Freeze { account, .. }
+        | TokenAdminMsg::Unfreeze { account, .. }
+        | TokenAdminMsg::SetWhitelistedLimit { account, .. } => {
+            validate_address(deps.api, account)?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// AssetFT denoms end in their issuer, native denoms have no such suffix
+fn is_smart_token(denom: &str) -> bool {
+    denom.contains('-')
+}
 
+/// Checks the denom is a Coreum smart token
+fn validate_smart_token(deps: Deps<CoreumQueries>, denom: String) -> Result<String, ContractError> {
+    let query = QueryRequest::Custom(CoreumQueries::AssetFT(assetft::Query::Token { denom: denom.clone() }));
+    deps.querier.query::<assetft::TokenResponse>(&query)
+        .map_err(|_| ContractError::InvalidInput(format!("{} is not a Coreum smart token", denom)))?;
+    Ok(denom)
+}
 
-    for member in msg.members {
-        MEMBERS.save(deps.storage, member.address.as_str(), &Member {
-            address: member.address.clone(),
-            weight: Uint128::from(member.weight),
-        })?;
+/// The weight of a member at the start of the given height, or now
+fn member_weight(deps: Deps<CoreumQueries>, address: &Addr, height: Option<u64>) -> StdResult<Option<Uint128>> {
+    let config = CONFIG.load(deps.storage)?;
+    if let Some(group) = config.group {
+        let weight = Cw4Contract::new(group).member_at_height(&deps.querier.into_empty(), address.to_string(), height)?;
+        return Ok(weight.map(Uint128::from));
     }
+    let member = match height {
+        Some(height) => MEMBERS.may_load_at_height(deps.storage, address.as_str(), height)?,
+        None => MEMBERS.may_load(deps.storage, address.as_str())?,
+    };
+    let member = member.map(|member| member.weight);
+    if config.governance_token.is_none() {
+        return Ok(member);
+    }
+    let stake = match height {
+        Some(height) => STAKES.may_load_at_height(deps.storage, address.as_str(), height)?,
+        None => STAKES.may_load(deps.storage, address.as_str())?,
+    };
+    match config.staking_mode {
+        StakingMode::Replace => Ok(stake),
+        StakingMode::Supplement => Ok(member.map(|weight| weight + stake.unwrap_or_default())),
+    }
+}
+
+fn load_member(deps: Deps<CoreumQueries>, address: &Addr, height: Option<u64>) -> Result<Member, ContractError> {
+    let weight = member_weight(deps, address, height)?
+        .ok_or_else(|| ContractError::NotMember { address: address.to_string() })?;
+    Ok(Member { address: address.clone(), weight })
+}
+
+fn load_proposal(storage: &dyn Storage, proposal_id: u64) -> Result<Proposal, ContractError> {
+    PROPOSALS.may_load(storage, &proposal_id.to_string())?
+        .ok_or(ContractError::ProposalNotFound { id: proposal_id })
+}
+
+fn load_round(storage: &dyn Storage, round_id: u64) -> Result<GrantRound, ContractError> {
+    ROUNDS.may_load(storage, &round_id.to_string())?
+        .ok_or(ContractError::RoundNotFound { id: round_id })
+}
+
+fn load_quadratic_round(storage: &dyn Storage, round_id: u64) -> Result<QuadraticRound, ContractError> {
+    QUADRATIC_ROUNDS.may_load(storage, &round_id.to_string())?
+        .ok_or(ContractError::QuadraticRoundNotFound { id: round_id })
+}
 
-    Ok(Response::default())
+fn load_application(storage: &dyn Storage, application_id: u64) -> Result<GrantApplication, ContractError> {
+    APPLICATIONS.may_load(storage, &application_id.to_string())?
+        .ok_or(ContractError::ApplicationNotFound { id: application_id })
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
-) -> Result<Response, ContractError> {
+) -> Result<Response<CoreumMsg>, ContractError> {
     match msg {
-        ExecuteMsg::Propose { title, description, recipient, amount } => execute_propose(deps, env, info, title, description, recipient,amount),
-        ExecuteMsg::Vote { proposal_id, approve } => execute_vote(deps, info, proposal_id, approve),
-        ExecuteMsg::Execute { proposal_id } => execute_execute(deps, env, proposal_id),  // Add env here
+        ExecuteMsg::Propose { title, description, msgs, latest, recipient, amount, schedule, action, url, content_hash } => {
+            execute_propose(
+                deps, env, info, title, description, msgs, latest, recipient, amount, schedule, action, url, content_hash,
+            )
+        }
+        ExecuteMsg::Vote { proposal_id, vote } => execute_vote(deps, env, info, proposal_id, vote),
+        ExecuteMsg::Execute { proposal_id } => execute_execute(deps, env, proposal_id),
+        ExecuteMsg::Close { proposal_id } => execute_close(deps, env, proposal_id),
+        ExecuteMsg::MemberChangedHook(msg) => execute_member_changed_hook(deps, env, info, msg),
+        ExecuteMsg::VoteRound { round_id, choices } => execute_vote_round(deps, env, info, round_id, choices),
+        ExecuteMsg::FinalizeRound { round_id } => execute_finalize_round(deps, env, round_id),
+        ExecuteMsg::Contribute { round_id, application } => execute_contribute(deps, env, info, round_id, application),
+        ExecuteMsg::FinalizeQuadraticRound { round_id } => execute_finalize_quadratic_round(deps, env, round_id),
+        ExecuteMsg::SubmitMilestone { proposal_id, index, deliverable } => {
+            execute_submit_milestone(deps, env, info, proposal_id, index, deliverable)
+        }
+        ExecuteMsg::ApproveMilestone { proposal_id, index } => execute_approve_milestone(deps, info, proposal_id, index),
+        ExecuteMsg::ReleaseMilestone { proposal_id, index } => execute_release_milestone(deps, env, proposal_id, index),
+        ExecuteMsg::CancelMilestone { proposal_id, index } => execute_cancel_milestone(deps, env, info, proposal_id, index),
+        ExecuteMsg::ClaimVested { proposal_id } => execute_claim_vested(deps, env, info, proposal_id),
+        ExecuteMsg::ClaimRecurring { proposal_id } => execute_claim_recurring(deps, env, info, proposal_id),
+        ExecuteMsg::Apply { title, description, requested, recipient } => {
+            execute_apply(deps, env, info, title, description, requested, recipient)
+        }
+        ExecuteMsg::Sponsor { application_id } => execute_sponsor(deps, env, info, application_id),
+        ExecuteMsg::ExpireApplication { application_id } => execute_expire_application(deps, env, application_id),
+        ExecuteMsg::AssignReviewers { proposal_id, reviewers } => {
+            execute_assign_reviewers(deps, env, info, proposal_id, reviewers)
+        }
+        ExecuteMsg::SubmitReview { proposal_id, score, comment_hash } => {
+            execute_submit_review(deps, env, info, proposal_id, score, comment_hash)
+        }
+        ExecuteMsg::UpdateRecipientLists { update } => execute_update_recipient_lists(deps, info, update),
+        ExecuteMsg::Pause { until } => execute_pause(deps, env, info, until),
+        ExecuteMsg::Unpause {} => execute_unpause(deps, info),
+        ExecuteMsg::Veto { proposal_id, reason } => execute_veto(deps, env, info, proposal_id, reason),
+        ExecuteMsg::Stake {} => execute_stake(deps, env, info),
+        ExecuteMsg::Unstake { amount } => execute_unstake(deps, env, info, amount),
+        ExecuteMsg::Claim {} => execute_claim(deps, env, info),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, info, msg),
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn execute_propose(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
     env: Env,
     info: MessageInfo,
     title: String,
     description: String,
-    recipient: Option<Addr>,
+    msgs: Vec<CosmosMsg<CoreumMsg>>,
+    latest: Option<Expiration>,
+    recipient: Option<String>,
     amount: Option<Uint128>,
-) -> Result<Response, ContractError> {
-    let sender_addr = info.sender.as_str();
-    let member_opt = MEMBERS.load(deps.storage, sender_addr);
+    schedule: Option<PayoutSchedule>,
+    action: Option<ProposalAction>,
+    url: Option<String>,
+    content_hash: Option<String>,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    load_member(deps.as_ref(), &info.sender, None)?;
 
-    // This automatically returns Unauthorized if the sender is not found in MEMBERS
-    if member_opt.is_err() {
-        return Err(ContractError::Unauthorized {});
+    let config = CONFIG.load(deps.storage)?;
+    let limits = config.content_limits;
+    validate_content(&limits, &title, &description)?;
+
+    let now = env.block.time.seconds();
+    let voting_end = match latest {
+        None | Some(Expiration::Never {}) => now + VOTING_PERIOD,
+        Some(_) if config.min_reviews > 0 => {
+            return Err(ContractError::InvalidInput(
+                "Proposals that go through review cannot set their own expiry".to_string(),
+            ))
+        }
+        Some(Expiration::AtTime(time)) if time.seconds() > now && time.seconds() <= now + VOTING_PERIOD => {
+            time.seconds()
+        }
+        Some(Expiration::AtTime(_)) => {
+            return Err(ContractError::InvalidInput(format!(
+                "Voting must end within the next {} seconds", VOTING_PERIOD
+            )))
+        }
+        Some(Expiration::AtHeight(_)) => {
+            return Err(ContractError::InvalidInput("Voting ends at a time, not a block height".to_string()))
+        }
+    };
+    if let Some(url) = &url {
+        validate_url(&limits, url)?;
     }
+    let content_hash = content_hash.map(|hash| validate_content_hash(&hash)).transpose()?;
 
-    // Get the current proposal count and increment it for a new unique ID
-    let mut proposal_count = PROPOSAL_COUNT.load(deps.storage).unwrap_or_default();
-    proposal_count += 1;
+    let recipient = match recipient {
+        Some(recipient) => validate_address(deps.api, &recipient)?,
+        None => info.sender.clone(),
+    };
 
-    // Save the updated count back to storage
-    PROPOSAL_COUNT.save(deps.storage, &proposal_count)?;
+    if let Some(action) = &action {
+        if amount.is_some() || schedule.is_some() {
+            return Err(ContractError::InvalidInput("An action proposal cannot also pay a grant".to_string()));
+        }
+        if !msgs.is_empty() {
+            return Err(ContractError::InvalidInput("An action proposal cannot also carry messages".to_string()));
+        }
+        validate_action(deps.as_ref(), &env, action)?;
+    } else {
+        ensure_recipient_allowed(deps.as_ref(), &recipient)?;
+        check_proposal_msgs(deps.as_ref(), &msgs)?;
+    }
+
+    let (amount, payout) = match schedule {
+        None => (amount.unwrap_or_else(Uint128::zero), Payout::LumpSum),
+        Some(PayoutSchedule::Milestones { reviewer, milestones }) => {
+            if milestones.is_empty() {
+                return Err(ContractError::InvalidInput("At least one milestone is required".to_string()));
+            }
+            if milestones.iter().any(|milestone| milestone.amount.is_zero()) {
+                return Err(ContractError::InvalidInput("Milestone amount must be positive".to_string()));
+            }
 
+            let total = milestones.iter()
+                .try_fold(Uint128::zero(), |total, milestone| total.checked_add(milestone.amount))
+                .map_err(|_| ContractError::InvalidInput("Milestone amounts overflow".to_string()))?;
+            if amount.is_some_and(|amount| amount != total) {
+                return Err(ContractError::InvalidInput("Amount must equal the sum of the milestones".to_string()));
+            }
 
+            let milestones = milestones.into_iter()
+                .map(|milestone| Milestone {
+                    description: milestone.description,
+                    amount: milestone.amount,
+                    deliverable: None,
+                    approvals: vec![],
+                    submitted_height: None,
+                    status: MilestoneStatus::Pending,
+                })
+                .collect();
+            let reviewer = reviewer.map(|reviewer| validate_address(deps.api, &reviewer)).transpose()?;
+            (total, Payout::Milestones { reviewer, milestones })
+        }
+        Some(PayoutSchedule::Vesting { start, end, cliff }) => {
+            let amount = amount.unwrap_or_else(Uint128::zero);
+            if amount.is_zero() {
+                return Err(ContractError::InvalidInput("A vesting grant needs an amount".to_string()));
+            }
+            if end <= start {
+                return Err(ContractError::InvalidInput("Vesting must end after it starts".to_string()));
+            }
+            if cliff.is_some_and(|cliff| cliff < start || cliff > end) {
+                return Err(ContractError::InvalidInput("Cliff must fall within the vesting period".to_string()));
+            }
+            (amount, Payout::Vesting { start, end, cliff, claimed: Uint128::zero(), terminated_at: None })
+        }
+        Some(PayoutSchedule::Recurring { period_amount, interval, periods }) => {
+            if period_amount.is_zero() || interval == 0 || periods == 0 {
+                return Err(ContractError::InvalidInput(
+                    "Recurring grants need a positive amount, interval and number of periods".to_string(),
+                ));
+            }
+
+            let total = period_amount.checked_mul(Uint128::from(periods))
+                .map_err(|_| ContractError::InvalidInput("Recurring grant total overflows".to_string()))?;
+            if amount.is_some_and(|amount| amount != total) {
+                return Err(ContractError::InvalidInput("Amount must equal the sum of all periods".to_string()));
+            }
+            (total, Payout::Recurring { period_amount, interval, periods, start: None, claimed: 0, stopped: false })
+        }
+        Some(PayoutSchedule::Cw20 { contract }) => {
+            let amount = amount.unwrap_or_else(Uint128::zero);
+            if amount.is_zero() {
+                return Err(ContractError::InvalidInput("A CW20 grant needs an amount".to_string()));
+            }
+            let contract = validate_address(deps.api, &contract)?;
+            if !config.cw20_tokens.contains(&contract) {
+                return Err(ContractError::InvalidInput(format!("{} is not an accepted CW20 token", contract)));
+            }
+            (amount, Payout::Cw20 { contract })
+        }
+    };
 
-    let voting_period = 604800; // 7 days in seconds
-    let proposal = Proposal {
-        id: 0, // This should be a unique ID, possibly increment based on the last ID
+    let proposal = save_proposal(deps, &env, Proposal {
+        id: 0,
+        proposer: info.sender.clone(),
         title,
         description,
+        url,
+        content_hash,
         votes_for: Uint128::zero(),
         votes_against: Uint128::zero(),
+        votes_abstain: Uint128::zero(),
+        start_height: env.block.height,
         executed: false,
-        amount: amount.unwrap_or_else(Uint128::zero),
-        recipient: recipient.unwrap_or(info.sender),
-        voting_end: env.block.time.seconds() + voting_period,
-    };
+        amount,
+        recipient,
+        voting_end,
+        review_end: 0,
+        msgs,
+        payout,
+        reserved: Uint128::zero(),
+        action,
+        status: ProposalStatus::Open,
+        reviewers: vec![],
+        review_count: 0,
+        review_score: 0,
+    })?;
+
+    Ok(Response::default()
+        .add_attribute("action", "propose")
+        .add_event(events::proposal_created(&proposal, &info.sender)))
+}
+
+/// Stores a new proposal under the next unique ID and returns it as stored
+fn save_proposal(deps: DepsMut<CoreumQueries>, env: &Env, mut proposal: Proposal) -> StdResult<Proposal> {
+    // The current count is the next unique ID
+    let proposal_id = PROPOSAL_COUNT.load(deps.storage).unwrap_or_default();
+
+    // Save the updated count back to storage
+    PROPOSAL_COUNT.save(deps.storage, &(proposal_id + 1))?;
 
-    PROPOSALS.save(deps.storage, &proposal.id.to_string(), &proposal)?;
+    proposal.id = proposal_id;
+    if CONFIG.load(deps.storage)?.min_reviews > 0 {
+        // The voting period starts once the reviews are in, a proposal nobody reviews can be closed
+        proposal.status = ProposalStatus::InReview;
+        proposal.voting_end = 0;
+        proposal.review_end = env.block.time.seconds() + REVIEW_PERIOD;
+    } else {
+        proposal.status = ProposalStatus::Open;
+    }
+    PROPOSALS.save(deps.storage, &proposal_id.to_string(), &proposal)?;
 
-    Ok(Response::default().add_attribute("action", "propose"))
+    Ok(proposal)
 }
 
 fn execute_vote(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
     info: MessageInfo,
     proposal_id: u64,
-    approve: bool,
-) -> Result<Response, ContractError> {
-    let sender_addr = info.sender.as_str();
-    let member_opt = MEMBERS.load(deps.storage, sender_addr); 
+    vote: Vote,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let mut proposal = load_proposal(deps.storage, proposal_id)?;
+    let member = load_member(deps.as_ref(), &info.sender, Some(proposal.start_height))?;
 
-    if member_opt.is_err() {
-        return Err(ContractError::Unauthorized {});
+    if proposal.status == ProposalStatus::InReview {
+        return Err(ContractError::InReview {});
+    }
+    if proposal.executed || proposal.status != ProposalStatus::Open || env.block.time.seconds() >= proposal.voting_end {
+        return Err(ContractError::VotingClosed {});
     }
-    // We are trying to extract the value if it's Some or None
-
-    let member = member_opt.unwrap();
 
-    let mut proposal = PROPOSALS.load(deps.storage, &proposal_id.to_string())?;
+    let key = (proposal_id.to_string(), info.sender.to_string());
+    if BALLOTS.has(deps.storage, (&key.0, &key.1)) {
+        return Err(ContractError::AlreadyVoted {});
+    }
+    BALLOTS.save(deps.storage, (&key.0, &key.1), &Ballot { vote, weight: member.weight })?;
 
-    if approve {
-        proposal.votes_for += member.weight;
-    } else {
-        proposal.votes_against += member.weight;
+    match vote {
+        Vote::Yes => proposal.votes_for += member.weight,
+        // A veto ballot is a strong no, it has nothing to do with the veto council
+        Vote::No | Vote::Veto => proposal.votes_against += member.weight,
+        Vote::Abstain => proposal.votes_abstain += member.weight,
     }
 
     PROPOSALS.save(deps.storage, &proposal_id.to_string(), &proposal)?;
 
-    Ok(Response::default())
+    Ok(Response::default()
+        .add_attribute("action", "vote")
+        .add_event(events::vote_cast(&proposal, &info.sender, vote, member.weight)))
 }
 
 fn execute_execute(
-    deps: DepsMut,
-    _env: Env,
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
     proposal_id: u64,
-) -> Result<Response, ContractError> {
-    let mut proposal = PROPOSALS.load(deps.storage, &proposal_id.to_string())?;
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let mut proposal = load_proposal(deps.storage, proposal_id)?;
 
-    
+    if proposal.executed {
+        return Err(ContractError::AlreadyExecuted {});
+    }
+    // The DAO must still be able to replace a guardian that keeps the contract paused
+    if !matches!(proposal.action, Some(ProposalAction::SetGuardian { .. })) {
+        ensure_not_paused(deps.as_ref(), &env)?;
+    }
+    if matches!(proposal.status, ProposalStatus::Vetoed { .. }) {
+        return Err(ContractError::Vetoed {});
+    }
+    if proposal.status == ProposalStatus::Rejected {
+        return Err(ContractError::NotPassed {});
+    }
 
+    let config = CONFIG.load(deps.storage)?;
+    let total = total_weight(deps.as_ref(), Some(proposal.start_height))?;
+    if proposal_passed(&proposal, total, config.quorum, env.block.time.seconds()) {
+        // Passed proposals wait out the timelock so the veto council can step in
+        let timelock = config.timelock;
+        let until = proposal.voting_end + timelock;
+        if timelock > 0 && env.block.time.seconds() < until {
+            return Err(ContractError::Timelocked { until });
+        }
 
-    if proposal.votes_for > proposal.votes_against {
         let recipient = &proposal.recipient;
-        let amount = &proposal.amount;
-
-        let current_balance = Uint128::zero();
+        let amount = proposal.amount;
 
-        if current_balance < *amount {
-            return Err(ContractError::Std(StdError::generic_err("Insufficient funds")));
+        // Bank sends among the messages spend the same treasury as the grant, CW20 grants are paid from
+        // the recorded deposits instead
+        let sent = check_proposal_msgs(deps.as_ref(), &proposal.msgs)?;
+        let rate = payout_fee_rate(deps.as_ref(), &env)?;
+        let reservation = grant_reservation(&proposal, rate);
+        match proposal.payout {
+            Payout::Cw20 { .. } => ensure_treasury(deps.as_ref(), &env, sent)?,
+            // The lump sum's fees are checked when it is paid
+            Payout::LumpSum => ensure_treasury(deps.as_ref(), &env, amount.saturating_add(sent))?,
+            _ => ensure_treasury(deps.as_ref(), &env, reservation.saturating_add(sent))?,
         }
 
-        let transfer = BankMsg::Send {
-            to_address: recipient.clone().into(),
-            amount: vec![Coin {
-                denom: "udevcore".to_string(),
-                amount: amount.clone(),
-            }],
-        };
-
         proposal.executed = true;
+        proposal.reserved = reservation;
+        if let Payout::Recurring { start, .. } = &mut proposal.payout {
+            *start = Some(env.block.time.seconds());
+        }
         PROPOSALS.save(deps.storage, &proposal_id.to_string(), &proposal)?;
 
-        let cosmos_msg = cosmwasm_std::CosmosMsg::Bank(transfer);
+        let executed = events::proposal_executed(&proposal);
+        let msgs = proposal.msgs.clone();
+        // Action proposals cannot carry messages, so nothing is dispatched past the pause exemption
+        if let Some(action) = proposal.action {
+            return Ok(execute_action(deps, env, proposal_id, action)?.add_event(executed));
+        }
+
+        // The lists may have changed while the proposal was being voted on
+        ensure_recipient_allowed(deps.as_ref(), recipient)?;
+
+        if let Payout::Cw20 { contract } = &proposal.payout {
+            withdraw_cw20(deps.storage, contract, amount)?;
+            return Ok(Response::new()
+                .add_attribute("method", "execute_execute")
+                .add_attribute("recipient", recipient.to_string())
+                .add_attribute("amount", amount.to_string())
+                .add_event(executed)
+                .add_message(cw20_payout(contract, recipient, amount)?)
+                .add_event(events::grant_paid(&proposal, amount))
+                .add_messages(mint_certificate(deps.storage, &env, &proposal, amount)?)
+                .add_messages(msgs));
+        }
+        if !matches!(proposal.payout, Payout::LumpSum) {
+            // Passing only sets the grant aside, it is paid out by milestone, as it vests or period by period
+            RESERVED.update(deps.storage, |reserved| -> StdResult<_> { Ok(reserved + reservation) })?;
+
+            return Ok(Response::new()
+                .add_attribute("method", "execute_execute")
+                .add_attribute("recipient", recipient.to_string())
+                .add_attribute("reserved", reservation.to_string())
+                .add_event(executed)
+                .add_messages(msgs));
+        }
 
-        return Ok(Response::new()
-            .add_message(cosmos_msg) // TODO: use coreum message instead?
+        let mut res = Response::new()
             .add_attribute("method", "execute_execute")
             .add_attribute("recipient", recipient.to_string())
-            .add_attribute("amount", amount.to_string()));
+            .add_attribute("amount", amount.to_string())
+            .add_event(executed);
+        // Plain cw3 proposals only carry messages
+        if !amount.is_zero() {
+            ensure_payable(deps.as_ref(), &env, &[(recipient, amount)])?;
+            res = res
+                .add_message(grant_payout(&grant_denom(deps.storage)?, recipient, amount))
+                .add_event(events::grant_paid(&proposal, amount))
+                .add_messages(mint_certificate(deps.storage, &env, &proposal, amount)?);
+        }
+        return Ok(res.add_messages(msgs));
     }
 
-    Ok(Response::default())
+    Err(ContractError::NotPassed {})
 }
 
-
-
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::GetProposal { proposal_id } => query_proposal(deps, proposal_id),
-        QueryMsg::ListProposals {} => query_all_proposals(deps),
-        QueryMsg::GetMember { address } => query_member(deps, address),
-        QueryMsg::ListMembers {} => query_all_members(deps),
+fn execute_close(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    proposal_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let mut proposal = load_proposal(deps.storage, proposal_id)?;
+    if proposal.executed {
+        return Err(ContractError::AlreadyExecuted {});
+    }
+    let deadline = match proposal.status {
+        ProposalStatus::Open => proposal.voting_end,
+        ProposalStatus::InReview => proposal.review_end,
+        _ => return Err(ContractError::InvalidInput(format!("Proposal {} is not open", proposal_id))),
+    };
+    if env.block.time.seconds() < deadline {
+        return Err(ContractError::VotingOpen {});
+    }
+    let quorum = CONFIG.load(deps.storage)?.quorum;
+    let total = total_weight(deps.as_ref(), Some(proposal.start_height))?;
+    if proposal_passed(&proposal, total, quorum, env.block.time.seconds()) {
+        return Err(ContractError::InvalidInput(format!("Proposal {} passed and cannot be closed", proposal_id)));
     }
-}
 
-fn query_proposal(deps: Deps, proposal_id: u64) -> StdResult<Binary> {
-    let proposal = PROPOSALS.load(deps.storage, &proposal_id.to_string())
-        .map_err(|_| StdError::not_found("Proposal"))?;
-    to_binary(&proposal)
-}
+    proposal.status = ProposalStatus::Rejected;
+    PROPOSALS.save(deps.storage, &proposal_id.to_string(), &proposal)?;
 
-fn query_all_proposals(deps: Deps) -> StdResult<Binary> {
-    let proposals = PROPOSALS.range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
-        .map(|item| {
-            let (_key, proposal) = item?;
-            Ok(proposal)
-        })
-        .collect::<StdResult<Vec<Proposal>>>()?;
-    to_binary(&proposals)
+    Ok(Response::new()
+        .add_attribute("action", "close")
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_event(events::proposal_status_changed(&proposal)))
 }
 
-fn query_member(deps: Deps, address: Addr) -> StdResult<Binary> {
-    let member = MEMBERS.load(deps.storage, address.as_str())
-        .map_err(|_| StdError::not_found("Member"))?;
-    to_binary(&member)
-}
+/// Keeps the cached total weight in step with the cw4 group, member weights are always queried
+fn execute_member_changed_hook(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    msg: MemberChangedHookMsg,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.group.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
 
-fn query_all_members(deps: Deps) -> StdResult<Binary> {
-    let members = MEMBERS.range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
-        .map(|item| {
-            let (_key, member) = item?;
-            Ok(member)
-        })
-        .collect::<StdResult<Vec<Member>>>()?;
-    to_binary(&members)
+    let mut total = TOTAL_WEIGHT.load(deps.storage)?;
+    let mut res = Response::new().add_attribute("action", "member_changed_hook");
+    for diff in msg.diffs {
+        let address = deps.api.addr_validate(&diff.key)?;
+        let old = Uint128::from(diff.old.unwrap_or_default());
+        let new = Uint128::from(diff.new.unwrap_or_default());
+        total = total.checked_sub(old).map_err(StdError::from)? + new;
+        res = res.add_event(events::member_changed(&address, new));
+    }
+    TOTAL_WEIGHT.save(deps.storage, &total, env.block.height)?;
+
+    Ok(res)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{from_binary, Addr, Uint128};
-    use crate::state::Member;
+fn execute_stake(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let denom = config.governance_token
+        .ok_or_else(|| ContractError::InvalidInput("The DAO has no governance token to stake".to_string()))?;
+    if config.staking_mode == StakingMode::Supplement && MEMBERS.may_load(deps.storage, info.sender.as_str())?.is_none() {
+        return Err(ContractError::NotMember { address: info.sender.to_string() });
+    }
+    let amount = cw_utils::must_pay(&info, &denom)
+        .map_err(|err| ContractError::InvalidInput(err.to_string()))?;
 
+    let stake = STAKES.may_load(deps.storage, info.sender.as_str())?.unwrap_or_default() + amount;
+    STAKES.save(deps.storage, info.sender.as_str(), &stake, env.block.height)?;
+    TOTAL_WEIGHT.update(deps.storage, env.block.height, |total| -> StdResult<_> { Ok(total.unwrap_or_default() + amount) })?;
+    let weight = member_weight(deps.as_ref(), &info.sender, None)?.unwrap_or_default();
 
-    #[test]
-    fn proper_instantiation() {
-        let mut deps = mock_dependencies();
-        
-        let members = vec![
-            Member {
-                address: Addr::unchecked("addr1"),
-                weight: Uint128::from(10_u128),
-            },
-            Member {
-                address: Addr::unchecked("addr2"),
-                weight: Uint128::from(20_u128),
+    Ok(Response::new()
+        .add_attribute("action", "stake")
+        .add_attribute("amount", amount.to_string())
+        .add_event(events::member_changed(&info.sender, weight)))
+}
 
-            },
-        ];
+fn execute_unstake(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.governance_token.is_none() {
+        return Err(ContractError::InvalidInput("The DAO has no governance token to stake".to_string()));
+    }
+    let staked = STAKES.may_load(deps.storage, info.sender.as_str())?.unwrap_or_default();
+    if amount.is_zero() || amount > staked {
+        return Err(ContractError::InvalidInput(format!("Can unstake between 1 and {}, got {}", staked, amount)));
+    }
 
-        let msg = InstantiateMsg {members};
-        let info = mock_info("creator", &[]);
-        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+    // Ballots already cast keep their weight, the snapshot still holds the old stake
+    let stake = staked - amount;
+    if stake.is_zero() {
+        STAKES.remove(deps.storage, info.sender.as_str(), env.block.height)?;
+    } else {
+        STAKES.save(deps.storage, info.sender.as_str(), &stake, env.block.height)?;
     }
+    TOTAL_WEIGHT.update(deps.storage, env.block.height, |total| -> StdResult<_> {
+        Ok(total.unwrap_or_default().checked_sub(amount)?)
+    })?;
+    let release_at = env.block.time.plus_seconds(config.unbonding_period);
+    CLAIMS.create_claim(deps.storage, &info.sender, amount, Expiration::AtTime(release_at))?;
+    let weight = member_weight(deps.as_ref(), &info.sender, None)?.unwrap_or_default();
 
-    #[test]
-    fn proposal_creation() {
-        let mut deps = mock_dependencies();
+    Ok(Response::new()
+        .add_attribute("action", "unstake")
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("release_at", release_at.seconds().to_string())
+        .add_event(events::member_changed(&info.sender, weight)))
+}
 
-        let members = vec![
-            Member {
-                address: Addr::unchecked("addr1"),
-                weight: Uint128::from(10_u128),
-            },
-        ];
+fn execute_claim(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let denom = CONFIG.load(deps.storage)?.governance_token
+        .ok_or_else(|| ContractError::InvalidInput("The DAO has no governance token to stake".to_string()))?;
+    let amount = CLAIMS.claim_tokens(deps.storage, &info.sender, &env.block, None)?;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
 
-        let msg = InstantiateMsg { members };
-        let info = mock_info("creator", &[]);
-        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    Ok(Response::new()
+        .add_message(BankMsg::Send { to_address: info.sender.to_string(), amount: vec![Coin { denom, amount }] })
+        .add_attribute("action", "claim")
+        .add_attribute("amount", amount.to_string())
+        .add_event(events::stake_claimed(&info.sender, amount)))
+}
 
-        // Propose
-        let info = mock_info("addr1", &[]);
-        let msg = ExecuteMsg::Propose {
-            title: "Test Proposal".to_string(),
-            description: "Description for test".to_string(),
-            amount: Some(Uint128::from(100_u128)),
-            recipient: Some(Addr::unchecked("recipient_address")),
-        };
-        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+fn validate_action(deps: Deps<CoreumQueries>, env: &Env, action: &ProposalAction) -> Result<(), ContractError> {
+    match action {
+        ProposalAction::TerminateStream { proposal_id } => {
+            let target = load_proposal(deps.storage, *proposal_id)?;
+            if !matches!(target.payout, Payout::Vesting { .. }) {
+                return Err(ContractError::InvalidInput(format!("Proposal {} is not a vesting grant", proposal_id)));
+            }
+        }
+        ProposalAction::StopRecurring { proposal_id } => {
+            let target = load_proposal(deps.storage, *proposal_id)?;
+            if !matches!(target.payout, Payout::Recurring { .. }) {
+                return Err(ContractError::InvalidInput(format!("Proposal {} is not a recurring grant", proposal_id)));
+            }
+        }
+        ProposalAction::UpdateReviewers { add, remove } => {
+            if add.is_empty() && remove.is_empty() {
+                return Err(ContractError::InvalidInput("No reviewers to add or remove".to_string()));
+            }
+            for reviewer in add.iter().chain(remove) {
+                validate_address(deps.api, reviewer)?;
+            }
+        }
+        ProposalAction::UpdateCw20Tokens { add, remove } => {
+            if add.is_empty() && remove.is_empty() {
+                return Err(ContractError::InvalidInput("No CW20 tokens to add or remove".to_string()));
+            }
+            for token in add.iter().chain(remove) {
+                validate_address(deps.api, token)?;
+            }
+        }
+        ProposalAction::Clawback { proposal_id, reason } => {
+            let target = load_proposal(deps.storage, *proposal_id)?;
+            if matches!(target.payout, Payout::LumpSum | Payout::Cw20 { .. }) {
+                return Err(ContractError::InvalidInput(format!("Proposal {} holds no escrowed funds", proposal_id)));
+            }
+            if reason.trim().is_empty() {
+                return Err(ContractError::InvalidInput("A clawback needs a reason".to_string()));
+            }
+        }
+        ProposalAction::UpdateRecipientLists { update } => {
+            if *update == RecipientListUpdate::default() {
+                return Err(ContractError::InvalidInput("No recipients to update".to_string()));
+            }
+            validate_recipient_list_update(deps.api, update)?;
+        }
+        ProposalAction::SetAllowlistMode { .. } => {}
+        ProposalAction::SetCompliance { address } => {
+            if let Some(address) = address {
+                validate_address(deps.api, address)?;
+            }
+        }
+        ProposalAction::SetContentLimits { limits } => validate_content_limits(limits)?,
+        ProposalAction::SetGuardian { address } => {
+            if let Some(address) = address {
+                validate_address(deps.api, address)?;
+            }
+        }
+        ProposalAction::SetVetoCouncil { council } => {
+            if let Some(council) = council {
+                validate_veto_council(deps.api, council, CONFIG.load(deps.storage)?.timelock)?;
+            }
+        }
+        ProposalAction::UpdateMembers { add, remove } => {
+            let config = CONFIG.load(deps.storage)?;
+            if !has_member_list(config.group.is_some(), config.governance_token.is_some(), &config.staking_mode) {
+                return Err(ContractError::InvalidInput("Members are managed by the group or by staking".to_string()));
+            }
+            if add.is_empty() && remove.is_empty() {
+                return Err(ContractError::InvalidInput("No members to add or remove".to_string()));
+            }
+            validate_members(deps.api, add.clone())?;
+            for address in remove {
+                validate_address(deps.api, address)?;
+            }
+        }
+        ProposalAction::AdministerToken { msg } => validate_token_admin(deps, env, msg)?,
+        ProposalAction::CreateRound { budget, applications, .. } => {
+            validate_round(deps, *budget, applications.clone())?;
+        }
+        ProposalAction::CreateQuadraticRound { matching_pool, contribution_cap, applications, .. } => {
+            validate_quadratic_round(deps, *matching_pool, *contribution_cap, applications.clone())?;
+        }
     }
+    Ok(())
+}
 
-    #[test]
-    fn vote_for_proposal() {
+fn execute_action(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    proposal_id: u64,
+    action: ProposalAction,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let res = Response::new()
+        .add_attribute("method", "execute_execute")
+        .add_attribute("proposal_id", proposal_id.to_string());
+
+    match action {
+        ProposalAction::TerminateStream { proposal_id: stream_id } => {
+            let mut stream = load_proposal(deps.storage, stream_id)?;
+            let rate = payout_fee_rate(deps.as_ref(), &env)?;
+            let now = env.block.time.seconds();
+            let vested = vested_amount(&stream, now);
+
+            let recipient = stream.recipient.clone();
+            let total = stream.amount;
+            let claimable = match &mut stream.payout {
+                Payout::Vesting { claimed, terminated_at, .. } if stream.executed && terminated_at.is_none() => {
+                    *terminated_at = Some(now);
+                    let claimable = vested - *claimed;
+                    *claimed = vested;
+                    claimable
+                }
+                _ => return Err(ContractError::InvalidInput(format!("Proposal {} is not an active stream", stream_id))),
+            };
+            // Everything still reserved for the stream is either paid out now or goes back to the treasury
+            release_grant_reservation(deps.storage, &mut stream, rate)?;
+            PROPOSALS.save(deps.storage, &stream_id.to_string(), &stream)?;
+            let reclaimed = total - vested;
+
+            let mut res = res
+                .add_attribute("action", "terminate_stream")
+                .add_attribute("stream_id", stream_id.to_string())
+                .add_attribute("reclaimed", reclaimed.to_string())
+                .add_event(events::grant_ended(stream_id, "terminated", reclaimed));
+            if !claimable.is_zero() {
+                ensure_payable(deps.as_ref(), &env, &[(&recipient, claimable)])?;
+                res = res
+                    .add_message(grant_payout(&grant_denom(deps.storage)?, &recipient, claimable))
+                    .add_event(events::grant_paid(&stream, claimable));
+            }
+            Ok(res)
+        }
+        ProposalAction::StopRecurring { proposal_id: schedule_id } => {
+            let mut schedule = load_proposal(deps.storage, schedule_id)?;
+            let rate = payout_fee_rate(deps.as_ref(), &env)?;
+            let due = periods_due(&schedule, env.block.time.seconds());
+
+            let recipient = schedule.recipient.clone();
+            let (claimable, reclaimed) = match &mut schedule.payout {
+                Payout::Recurring { period_amount, periods, claimed, stopped, .. } if schedule.executed && !*stopped => {
+                    let claimable = *period_amount * Uint128::from(due - *claimed);
+                    let reclaimed = *period_amount * Uint128::from(*periods - due);
+                    *claimed = due;
+                    *stopped = true;
+                    (claimable, reclaimed)
+                }
+                _ => {
+                    return Err(ContractError::InvalidInput(format!(
+                        "Proposal {} is not an active recurring grant",
+                        schedule_id
+                    )))
+                }
+            };
+            release_grant_reservation(deps.storage, &mut schedule, rate)?;
+            PROPOSALS.save(deps.storage, &schedule_id.to_string(), &schedule)?;
+
+            let mut res = res
+                .add_attribute("action", "stop_recurring")
+                .add_attribute("schedule_id", schedule_id.to_string())
+                .add_attribute("reclaimed", reclaimed.to_string())
+                .add_event(events::grant_ended(schedule_id, "stopped", reclaimed));
+            if !claimable.is_zero() {
+                ensure_payable(deps.as_ref(), &env, &[(&recipient, claimable)])?;
+                res = res
+                    .add_message(grant_payout(&grant_denom(deps.storage)?, &recipient, claimable))
+                    .add_event(events::grant_paid(&schedule, claimable));
+            }
+            Ok(res)
+        }
+        ProposalAction::UpdateReviewers { add, remove } => {
+            let mut res = res
+                .add_attribute("action", "update_reviewers")
+                .add_attribute("added", add.len().to_string())
+                .add_attribute("removed", remove.len().to_string());
+            for reviewer in &add {
+                let reviewer = validate_address(deps.api, reviewer)?;
+                REVIEWERS.save(deps.storage, reviewer.as_str(), &reviewer)?;
+                res = res.add_event(events::reviewer_changed(&reviewer, true));
+            }
+            for reviewer in &remove {
+                let reviewer = validate_address(deps.api, reviewer)?;
+                REVIEWERS.remove(deps.storage, reviewer.as_str());
+                res = res.add_event(events::reviewer_changed(&reviewer, false));
+            }
+            Ok(res)
+        }
+        ProposalAction::Clawback { proposal_id: grant_id, reason } => {
+            let mut grant = load_proposal(deps.storage, grant_id)?;
+            if !grant.executed || grant.status == ProposalStatus::ClawedBack {
+                return Err(ContractError::InvalidInput(format!("Proposal {} has no funds to claw back", grant_id)));
+            }
+
+            let now = env.block.time.seconds();
+            let total = grant.amount;
+            let vested = vested_amount(&grant, now);
+            let rate = payout_fee_rate(deps.as_ref(), &env)?;
+            let reclaimed = match &mut grant.payout {
+                Payout::LumpSum | Payout::Cw20 { .. } => Uint128::zero(),
+                Payout::Milestones { milestones, .. } => {
+                    let mut reclaimed = Uint128::zero();
+                    for milestone in milestones.iter_mut() {
+                        if !matches!(milestone.status, MilestoneStatus::Released | MilestoneStatus::Cancelled) {
+                            milestone.status = MilestoneStatus::Cancelled;
+                            reclaimed += milestone.amount;
+                        }
+                    }
+                    reclaimed
+                }
+                Payout::Vesting { claimed, terminated_at, .. } => {
+                    // A terminated stream keeps its end and has already returned what had not vested by then
+                    let terminated = terminated_at.is_some();
+                    terminated_at.get_or_insert(now);
+                    if terminated { vested - *claimed } else { total - *claimed }
+                }
+                Payout::Recurring { period_amount, periods, claimed, stopped, .. } => {
+                    let reclaimed = if *stopped {
+                        Uint128::zero()
+                    } else {
+                        *period_amount * Uint128::from(*periods - *claimed)
+                    };
+                    *stopped = true;
+                    reclaimed
+                }
+            };
+            grant.status = ProposalStatus::ClawedBack;
+            release_grant_reservation(deps.storage, &mut grant, rate)?;
+            PROPOSALS.save(deps.storage, &grant_id.to_string(), &grant)?;
+            DELINQUENTS.save(deps.storage, grant.recipient.as_str(), &Delinquency {
+                address: grant.recipient.clone(),
+                proposal_id: grant_id,
+                reason: reason.clone(),
+            })?;
+
+            Ok(res
+                .add_attribute("action", "clawback")
+                .add_attribute("grant_id", grant_id.to_string())
+                .add_attribute("grantee", grant.recipient.to_string())
+                .add_attribute("reclaimed", reclaimed.to_string())
+                .add_event(events::proposal_status_changed(&grant).add_attribute(events::ATTR_REASON, reason))
+                .add_event(events::grant_ended(grant_id, "clawed_back", reclaimed)))
+        }
+        ProposalAction::UpdateRecipientLists { update } => {
+            let changes = apply_recipient_list_update(deps, &update)?;
+            Ok(res.add_attribute("action", "update_recipient_lists").add_events(changes))
+        }
+        ProposalAction::SetAllowlistMode { enabled } => {
+            CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+                config.allowlist_enabled = enabled;
+                Ok(config)
+            })?;
+            Ok(res
+                .add_attribute("action", "set_allowlist_mode")
+                .add_attribute("enabled", enabled.to_string())
+                .add_event(events::config_changed("allowlist_enabled", &enabled)?))
+        }
+        ProposalAction::SetCompliance { address } => {
+            let address = address.map(|address| validate_address(deps.api, &address)).transpose()?;
+            CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+                config.compliance = address.clone();
+                Ok(config)
+            })?;
+            Ok(res
+                .add_attribute("action", "set_compliance")
+                .add_event(events::config_changed("compliance", &address)?)
+                .add_attribute("compliance", address.map_or_else(|| "none".to_string(), |a| a.to_string())))
+        }
+        ProposalAction::SetContentLimits { limits } => {
+            validate_content_limits(&limits)?;
+            let event = events::config_changed("content_limits", &limits)?;
+            CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+                config.content_limits = limits;
+                Ok(config)
+            })?;
+            Ok(res.add_attribute("action", "set_content_limits").add_event(event))
+        }
+        ProposalAction::SetGuardian { address } => {
+            let address = address.map(|address| validate_address(deps.api, &address)).transpose()?;
+            CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+                config.guardian = address.clone();
+                config.paused_until = None;
+                Ok(config)
+            })?;
+            Ok(res
+                .add_attribute("action", "set_guardian")
+                .add_event(events::config_changed("guardian", &address)?)
+                .add_event(events::config_changed("paused_until", &None::<u64>)?)
+                .add_attribute("guardian", address.map_or_else(|| "none".to_string(), |a| a.to_string())))
+        }
+        ProposalAction::SetVetoCouncil { council } => {
+            let timelock = CONFIG.load(deps.storage)?.timelock;
+            let council = council.map(|council| validate_veto_council(deps.api, &council, timelock)).transpose()?;
+            CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+                config.veto_council = council.clone();
+                Ok(config)
+            })?;
+            Ok(res
+                .add_attribute("action", "set_veto_council")
+                .add_event(events::config_changed("veto_council", &council)?)
+                .add_attribute("enabled", council.is_some().to_string()))
+        }
+        ProposalAction::UpdateMembers { add, remove } => {
+            let class_id = CONFIG.load(deps.storage)?.membership_nft_class;
+            let mut res = res.add_attribute("action", "update_members");
+            let mut total = TOTAL_WEIGHT.load(deps.storage)?;
+            for member in validate_members(deps.api, add)? {
+                let previous = MEMBERS.may_load(deps.storage, member.address.as_str())?;
+                total = total - previous.map_or(Uint128::zero(), |previous| previous.weight) + member.weight;
+                MEMBERS.save(deps.storage, member.address.as_str(), &member, env.block.height)?;
+                res = res.add_event(events::member_changed(&member.address, member.weight));
+                if let Some(class_id) = &class_id {
+                    // A re-weighted member keeps their NFT, only new members get one
+                    match MEMBERSHIP_NFTS.may_load(deps.storage, member.address.as_str())? {
+                        Some(mut nft) => {
+                            nft.weight = member.weight;
+                            MEMBERSHIP_NFTS.save(deps.storage, member.address.as_str(), &nft)?;
+                        }
+                        None => res = res.add_messages(mint_membership_nft(deps.storage, class_id, &member, "member")?),
+                    }
+                }
+            }
+            for address in remove {
+                let address = validate_address(deps.api, &address)?;
+                let member = MEMBERS
+                    .may_load(deps.storage, address.as_str())?
+                    .ok_or_else(|| ContractError::NotMember { address: address.to_string() })?;
+                MEMBERS.remove(deps.storage, address.as_str(), env.block.height)?;
+                total -= member.weight;
+                res = res.add_event(events::member_changed(&address, Uint128::zero()));
+                if let Some(class_id) = &class_id {
+                    if let Some(burn) = burn_membership_nft(deps.storage, class_id, &address)? {
+                        res = res.add_message(burn);
+                    }
+                }
+            }
+            TOTAL_WEIGHT.save(deps.storage, &total, env.block.height)?;
+            Ok(res)
+        }
+        ProposalAction::UpdateCw20Tokens { add, remove } => {
+            let mut config = CONFIG.load(deps.storage)?;
+            for token in &add {
+                let token = validate_address(deps.api, token)?;
+                if !config.cw20_tokens.contains(&token) {
+                    config.cw20_tokens.push(token);
+                }
+            }
+            for token in &remove {
+                let token = validate_address(deps.api, token)?;
+                config.cw20_tokens.retain(|accepted| *accepted != token);
+            }
+            CONFIG.save(deps.storage, &config)?;
+            Ok(res
+                .add_attribute("action", "update_cw20_tokens")
+                .add_event(events::config_changed("cw20_tokens", &config.cw20_tokens)?))
+        }
+        ProposalAction::AdministerToken { msg } => {
+            // Tokens may have been spent or reserved while the proposal was open
+            if let TokenAdminMsg::Burn { coin } = &msg {
+                if coin.denom == grant_denom(deps.storage)? {
+                    ensure_treasury(deps.as_ref(), &env, coin.amount)?;
+                }
+            }
+            let event = events::token_administered(&msg);
+            let denom = msg.denom().to_string();
+            let msg = match msg {
+                TokenAdminMsg::Mint { coin } => assetft::Msg::Mint { coin },
+                TokenAdminMsg::Burn { coin } => assetft::Msg::Burn { coin },
+                TokenAdminMsg::Freeze { account, coin } => assetft::Msg::Freeze { account, coin },
+                TokenAdminMsg::Unfreeze { account, coin } => assetft::Msg::Unfreeze { account, coin },
+                TokenAdminMsg::GloballyFreeze { denom } => assetft::Msg::GloballyFreeze { denom },
+                TokenAdminMsg::GloballyUnfreeze { denom } => assetft::Msg::GloballyUnfreeze { denom },
+                TokenAdminMsg::SetWhitelistedLimit { account, coin } => assetft::Msg::SetWhitelistedLimit { account, coin },
+            };
+            Ok(res
+                .add_attribute("action", "administer_token")
+                .add_attribute("denom", denom)
+                .add_message(CoreumMsg::AssetFT(msg))
+                .add_event(event))
+        }
+        ProposalAction::CreateRound { title, description, method, budget, applications } => {
+            let round = create_round(deps, env, title, description, method, budget, applications)?;
+            Ok(res
+                .add_attribute("action", "create_round")
+                .add_attribute("round_id", round.id.to_string())
+                .add_attribute("budget", budget.to_string())
+                .add_event(events::round_created(
+                    round.id,
+                    events::tally_method_name(&round.method),
+                    budget,
+                    round.applications.len(),
+                    round.voting_end,
+                )))
+        }
+        ProposalAction::CreateQuadraticRound { title, description, matching_pool, contribution_cap, applications } => {
+            let round =
+                create_quadratic_round(deps, env, title, description, matching_pool, contribution_cap, applications)?;
+            Ok(res
+                .add_attribute("action", "create_quadratic_round")
+                .add_attribute("round_id", round.id.to_string())
+                .add_attribute("matching_pool", matching_pool.to_string())
+                .add_event(events::round_created(
+                    round.id,
+                    events::QUADRATIC_KIND,
+                    matching_pool,
+                    round.applications.len(),
+                    round.round_end,
+                )))
+        }
+    }
+}
+
+/// How many periods of a recurring grant have fallen due at `now`, claimed or not
+fn periods_due(proposal: &Proposal, now: u64) -> u32 {
+    match proposal.payout {
+        Payout::Recurring { interval, periods, start: Some(start), claimed, stopped, .. } => {
+            if stopped {
+                return claimed;
+            }
+            let elapsed = now.saturating_sub(start) / interval;
+            elapsed.min(periods as u64) as u32
+        }
+        _ => 0,
+    }
+}
+
+fn execute_claim_recurring(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    ensure_not_paused(deps.as_ref(), &env)?;
+    let mut proposal = load_proposal(deps.storage, proposal_id)?;
+    if info.sender != proposal.recipient {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !proposal.executed {
+        return Err(ContractError::InvalidInput("Proposal has not been executed".to_string()));
+    }
+    if proposal.status == ProposalStatus::ClawedBack {
+        return Err(ContractError::ClawedBack {});
+    }
+
+    let rate = payout_fee_rate(deps.as_ref(), &env)?;
+    let due = periods_due(&proposal, env.block.time.seconds());
+    let (amount, periods) = match &mut proposal.payout {
+        Payout::Recurring { period_amount, claimed, .. } => {
+            let periods = due - *claimed;
+            *claimed = due;
+            (*period_amount * Uint128::from(periods), periods)
+        }
+        _ => return Err(ContractError::InvalidInput("Proposal is not a recurring grant".to_string())),
+    };
+    if amount.is_zero() {
+        return Err(ContractError::InvalidInput("No period is due yet".to_string()));
+    }
+
+    release_grant_reservation(deps.storage, &mut proposal, rate)?;
+    PROPOSALS.save(deps.storage, &proposal_id.to_string(), &proposal)?;
+    ensure_payable(deps.as_ref(), &env, &[(&proposal.recipient, amount)])?;
+
+    Ok(Response::new()
+        .add_message(grant_payout(&grant_denom(deps.storage)?, &proposal.recipient, amount))
+        .add_event(events::grant_paid(&proposal, amount))
+        .add_attribute("action", "claim_recurring")
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("periods", periods.to_string())
+        .add_attribute("amount", amount.to_string()))
+}
+
+/// How much of a vesting grant has unlocked at `now`
+fn vested_amount(proposal: &Proposal, now: u64) -> Uint128 {
+    match proposal.payout {
+        Payout::Vesting { start, end, cliff, terminated_at, .. } => {
+            let now = terminated_at.map_or(now, |terminated_at| now.min(terminated_at));
+            if now < cliff.unwrap_or(start) || now <= start {
+                Uint128::zero()
+            } else if now >= end {
+                proposal.amount
+            } else {
+                proposal.amount.multiply_ratio(now - start, end - start)
+            }
+        }
+        _ => Uint128::zero(),
+    }
+}
+
+fn execute_claim_vested(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    ensure_not_paused(deps.as_ref(), &env)?;
+    let mut proposal = load_proposal(deps.storage, proposal_id)?;
+    if info.sender != proposal.recipient {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !proposal.executed {
+        return Err(ContractError::InvalidInput("Proposal has not been executed".to_string()));
+    }
+    if proposal.status == ProposalStatus::ClawedBack {
+        return Err(ContractError::ClawedBack {});
+    }
+
+    let rate = payout_fee_rate(deps.as_ref(), &env)?;
+    let vested = vested_amount(&proposal, env.block.time.seconds());
+    let amount = match &mut proposal.payout {
+        Payout::Vesting { claimed, .. } => {
+            let amount = vested - *claimed;
+            *claimed = vested;
+            amount
+        }
+        _ => return Err(ContractError::InvalidInput("Proposal is not a vesting grant".to_string())),
+    };
+    if amount.is_zero() {
+        return Err(ContractError::InvalidInput("Nothing has vested yet".to_string()));
+    }
+
+    release_grant_reservation(deps.storage, &mut proposal, rate)?;
+    PROPOSALS.save(deps.storage, &proposal_id.to_string(), &proposal)?;
+    ensure_payable(deps.as_ref(), &env, &[(&proposal.recipient, amount)])?;
+
+    Ok(Response::new()
+        .add_message(grant_payout(&grant_denom(deps.storage)?, &proposal.recipient, amount))
+        .add_event(events::grant_paid(&proposal, amount))
+        .add_attribute("action", "claim_vested")
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("amount", amount.to_string()))
+}
+
+/// Loads a milestone of an executed milestone grant together with its reviewer
+fn load_milestone(proposal: &mut Proposal, index: u32) -> Result<(Option<Addr>, &mut Milestone), ContractError> {
+    if !proposal.executed {
+        return Err(ContractError::InvalidInput("Proposal has not been executed".to_string()));
+    }
+    if proposal.status == ProposalStatus::ClawedBack {
+        return Err(ContractError::ClawedBack {});
+    }
+    match &mut proposal.payout {
+        Payout::Milestones { reviewer, milestones } => {
+            let reviewer = reviewer.clone();
+            let milestone = milestones.get_mut(index as usize)
+                .ok_or_else(|| ContractError::InvalidInput(format!("Unknown milestone {}", index)))?;
+            Ok((reviewer, milestone))
+        }
+        _ => Err(ContractError::InvalidInput("Proposal has no milestones".to_string())),
+    }
+}
+
+/// The total weight at the start of the given height, or now
+fn total_weight(deps: Deps<CoreumQueries>, height: Option<u64>) -> StdResult<Uint128> {
+    match height {
+        Some(height) => Ok(TOTAL_WEIGHT.may_load_at_height(deps.storage, height)?.unwrap_or_default()),
+        None => TOTAL_WEIGHT.load(deps.storage),
+    }
+}
+
+fn execute_submit_milestone(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    index: u32,
+    deliverable: String,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let mut proposal = load_proposal(deps.storage, proposal_id)?;
+    if info.sender != proposal.recipient {
+        return Err(ContractError::Unauthorized {});
+    }
+    if deliverable.trim().is_empty() {
+        return Err(ContractError::InvalidInput("Deliverable link is required".to_string()));
+    }
+
+    let (_reviewer, milestone) = load_milestone(&mut proposal, index)?;
+    match milestone.status {
+        // Resubmitting replaces the deliverable and starts the review again
+        MilestoneStatus::Pending | MilestoneStatus::Submitted => {
+            milestone.deliverable = Some(deliverable);
+            milestone.approvals = vec![];
+            milestone.submitted_height = Some(env.block.height);
+            milestone.status = MilestoneStatus::Submitted;
+        }
+        _ => return Err(ContractError::InvalidInput("Milestone can no longer be submitted".to_string())),
+    }
+    let event = events::milestone_changed(proposal_id, index, milestone);
+    PROPOSALS.save(deps.storage, &proposal_id.to_string(), &proposal)?;
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("action", "submit_milestone")
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("index", index.to_string()))
+}
+
+fn execute_approve_milestone(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    proposal_id: u64,
+    index: u32,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let mut proposal = load_proposal(deps.storage, proposal_id)?;
+    let (reviewer, milestone) = load_milestone(&mut proposal, index)?;
+
+    if milestone.status != MilestoneStatus::Submitted {
+        return Err(ContractError::InvalidInput("Milestone is not awaiting approval".to_string()));
+    }
+    let height = milestone.submitted_height;
+    if milestone.approvals.contains(&info.sender) {
+        return Err(ContractError::AlreadyVoted {});
+    }
+
+    if reviewer.as_ref() == Some(&info.sender) {
+        milestone.approvals.push(info.sender);
+        milestone.status = MilestoneStatus::Approved;
+    } else {
+        load_member(deps.as_ref(), &info.sender, height)?;
+        milestone.approvals.push(info.sender);
+
+        // Without the reviewer, a majority of member weight at submission has to sign off
+        let approved_weight = milestone.approvals.iter()
+            .filter_map(|approver| member_weight(deps.as_ref(), approver, height).transpose())
+            .sum::<StdResult<Uint128>>()?;
+        if approved_weight * Uint128::from(2u8) > total_weight(deps.as_ref(), height)? {
+            milestone.status = MilestoneStatus::Approved;
+        }
+    }
+    let event = events::milestone_changed(proposal_id, index, milestone);
+    PROPOSALS.save(deps.storage, &proposal_id.to_string(), &proposal)?;
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("action", "approve_milestone")
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("index", index.to_string()))
+}
+
+fn execute_release_milestone(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    proposal_id: u64,
+    index: u32,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    ensure_not_paused(deps.as_ref(), &env)?;
+    let mut proposal = load_proposal(deps.storage, proposal_id)?;
+    let rate = payout_fee_rate(deps.as_ref(), &env)?;
+    let recipient = proposal.recipient.clone();
+    let (_reviewer, milestone) = load_milestone(&mut proposal, index)?;
+
+    if milestone.status != MilestoneStatus::Approved {
+        return Err(ContractError::InvalidInput("Milestone has not been approved".to_string()));
+    }
+    milestone.status = MilestoneStatus::Released;
+    let amount = milestone.amount;
+    let event = events::milestone_changed(proposal_id, index, milestone);
+
+    release_grant_reservation(deps.storage, &mut proposal, rate)?;
+    PROPOSALS.save(deps.storage, &proposal_id.to_string(), &proposal)?;
+    ensure_payable(deps.as_ref(), &env, &[(&recipient, amount)])?;
+
+    let certificate = milestone_certificate(deps.storage, &env, &proposal)?;
+
+    Ok(Response::new()
+        .add_message(grant_payout(&grant_denom(deps.storage)?, &recipient, amount))
+        .add_messages(certificate)
+        .add_event(event)
+        .add_event(events::grant_paid(&proposal, amount))
+        .add_attribute("action", "release_milestone")
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("index", index.to_string())
+        .add_attribute("amount", amount.to_string()))
+}
+
+/// Mints the certificate of a milestone grant once every milestone is released. A grant with a
+/// cancelled milestone was not completed and earns none.
+fn milestone_certificate(storage: &mut dyn Storage, env: &Env, proposal: &Proposal) -> StdResult<Vec<CosmosMsg<CoreumMsg>>> {
+    match &proposal.payout {
+        Payout::Milestones { milestones, .. }
+            if milestones.iter().all(|milestone| milestone.status == MilestoneStatus::Released) =>
+        {
+            mint_certificate(storage, env, proposal, proposal.amount)
+        }
+        _ => Ok(vec![]),
+    }
+}
+
+fn execute_cancel_milestone(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    index: u32,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let mut proposal = load_proposal(deps.storage, proposal_id)?;
+    let rate = payout_fee_rate(deps.as_ref(), &env)?;
+    let recipient = proposal.recipient.clone();
+    let (reviewer, milestone) = load_milestone(&mut proposal, index)?;
+
+    // The grantee can give up a milestone, the reviewer can call it off
+    if info.sender != recipient && reviewer.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if matches!(milestone.status, MilestoneStatus::Released | MilestoneStatus::Cancelled) {
+        return Err(ContractError::InvalidInput("Milestone is already settled".to_string()));
+    }
+    milestone.status = MilestoneStatus::Cancelled;
+    let amount = milestone.amount;
+    let event = events::milestone_changed(proposal_id, index, milestone);
+
+    release_grant_reservation(deps.storage, &mut proposal, rate)?;
+    PROPOSALS.save(deps.storage, &proposal_id.to_string(), &proposal)?;
+    let certificate = milestone_certificate(deps.storage, &env, &proposal)?;
+
+    Ok(Response::new()
+        .add_messages(certificate)
+        .add_event(event)
+        .add_attribute("action", "cancel_milestone")
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("index", index.to_string())
+        .add_attribute("amount", amount.to_string()))
+}
+
+fn execute_apply(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    title: String,
+    description: String,
+    requested: Uint128,
+    recipient: Option<String>,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if requested.is_zero() {
+        return Err(ContractError::InvalidInput("Requested amount must be positive".to_string()));
+    }
+    // Sponsoring turns the application into a proposal, so it has to fit the same limits
+    validate_content(&config.content_limits, &title, &description)?;
+
+    let recipient = match recipient {
+        Some(recipient) => validate_address(deps.api, &recipient)?,
+        None => info.sender.clone(),
+    };
+    ensure_recipient_allowed(deps.as_ref(), &recipient)?;
+
+    let fee = info.funds.iter()
+        .find(|coin| coin.denom == config.grant_denom)
+        .map(|coin| coin.amount)
+        .unwrap_or_default();
+    if fee < config.application_fee {
+        return Err(ContractError::InvalidInput(format!(
+            "Applying requires a fee of {}{}",
+            config.application_fee, config.grant_denom
+        )));
+    }
+
+    // The fee is held until the application is sponsored or expires, with what refunding it costs
+    let reservation = gross_payout(fee, payout_fee_rate(deps.as_ref(), &env)?);
+    RESERVED.update(deps.storage, |reserved| -> StdResult<_> { Ok(reserved + reservation) })?;
+
+    let application_id = APPLICATION_COUNT.load(deps.storage).unwrap_or_default();
+    APPLICATION_COUNT.save(deps.storage, &(application_id + 1))?;
+
+    let application = GrantApplication {
+        id: application_id,
+        recipient,
+        applicant: info.sender,
+        title,
+        description,
+        requested,
+        fee,
+        reserved: reservation,
+        expires: env.block.time.seconds() + config.application_period,
+        status: ApplicationStatus::Pending,
+    };
+    APPLICATIONS.save(deps.storage, &application_id.to_string(), &application)?;
+
+    Ok(Response::new()
+        .add_event(events::application_changed(&application))
+        .add_attribute("action", "apply")
+        .add_attribute("application_id", application_id.to_string())
+        .add_attribute("fee", fee.to_string()))
+}
+
+fn load_pending_application(deps: Deps<CoreumQueries>, env: &Env, application_id: u64) -> Result<GrantApplication, ContractError> {
+    let application = load_application(deps.storage, application_id)?;
+    if application.status != ApplicationStatus::Pending {
+        return Err(ContractError::InvalidInput(format!("Application {} is no longer pending", application_id)));
+    }
+    if env.block.time.seconds() >= application.expires {
+        return Err(ContractError::InvalidInput(format!("Application {} has expired", application_id)));
+    }
+    Ok(application)
+}
+
+fn execute_sponsor(
+    mut deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    application_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    load_member(deps.as_ref(), &info.sender, None)?;
+    ensure_not_paused(deps.as_ref(), &env)?;
+
+    let mut application = load_pending_application(deps.as_ref(), &env, application_id)?;
+    ensure_recipient_allowed(deps.as_ref(), &application.recipient)?;
+
+    let proposal = save_proposal(deps.branch(), &env, Proposal {
+        id: 0,
+        proposer: info.sender.clone(),
+        title: application.title.clone(),
+        description: application.description.clone(),
+        url: None,
+        content_hash: None,
+        votes_for: Uint128::zero(),
+        votes_against: Uint128::zero(),
+        votes_abstain: Uint128::zero(),
+        start_height: env.block.height,
+        executed: false,
+        amount: application.requested,
+        recipient: application.recipient.clone(),
+        voting_end: env.block.time.seconds() + VOTING_PERIOD,
+        review_end: 0,
+        msgs: vec![],
+        payout: Payout::LumpSum,
+        reserved: Uint128::zero(),
+        action: None,
+        status: ProposalStatus::Open,
+        reviewers: vec![],
+        review_count: 0,
+        review_score: 0,
+    })?;
+
+    let proposal_id = proposal.id;
+    application.status = ApplicationStatus::Sponsored { proposal_id };
+    APPLICATIONS.save(deps.storage, &application_id.to_string(), &application)?;
+
+    // A sponsored application was not spam, so the fee goes back to the applicant
+    let fee = application.fee;
+    RESERVED.update(deps.storage, |reserved| -> StdResult<_> { Ok(reserved.saturating_sub(application.reserved)) })?;
+
+    let mut res = Response::new()
+        .add_attribute("action", "sponsor")
+        .add_attribute("application_id", application_id.to_string())
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_event(events::proposal_created(&proposal, &info.sender))
+        .add_event(events::application_changed(&application));
+    if !fee.is_zero() {
+        ensure_payable(deps.as_ref(), &env, &[(&application.applicant, fee)])?;
+        res = res.add_message(grant_payout(&grant_denom(deps.storage)?, &application.applicant, fee));
+    }
+    Ok(res)
+}
+
+fn execute_expire_application(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    application_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    ensure_not_paused(deps.as_ref(), &env)?;
+    let config = CONFIG.load(deps.storage)?;
+    let mut application = load_application(deps.storage, application_id)?;
+
+    if application.status != ApplicationStatus::Pending {
+        return Err(ContractError::InvalidInput(format!("Application {} is no longer pending", application_id)));
+    }
+    if env.block.time.seconds() < application.expires {
+        return Err(ContractError::InvalidInput(format!("Application {} has not expired yet", application_id)));
+    }
+
+    application.status = ApplicationStatus::Expired;
+    APPLICATIONS.save(deps.storage, &application_id.to_string(), &application)?;
+
+    let fee = application.fee;
+    RESERVED.update(deps.storage, |reserved| -> StdResult<_> { Ok(reserved.saturating_sub(application.reserved)) })?;
+
+    let mut res = Response::new()
+        .add_attribute("action", "expire_application")
+        .add_attribute("application_id", application_id.to_string())
+        .add_event(events::application_changed(&application));
+    if !fee.is_zero() {
+        let msg = if config.burn_expired_fees {
+            CosmosMsg::Bank(BankMsg::Burn { amount: vec![Coin { denom: config.grant_denom.clone(), amount: fee }] })
+        } else {
+            ensure_payable(deps.as_ref(), &env, &[(&application.applicant, fee)])?;
+            grant_payout(&config.grant_denom, &application.applicant, fee)
+        };
+        res = res.add_message(msg);
+    }
+    Ok(res)
+}
+
+/// The DAO itself, or any member other than the proposer, picks who reviews a proposal
+fn execute_assign_reviewers(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    reviewers: Vec<String>,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let mut proposal = load_proposal(deps.storage, proposal_id)?;
+    if info.sender != env.contract.address {
+        load_member(deps.as_ref(), &info.sender, None)?;
+        if info.sender == proposal.proposer {
+            return Err(ContractError::Unauthorized {});
+        }
+    }
+    if proposal.status != ProposalStatus::InReview {
+        return Err(ContractError::InvalidInput("The proposal is no longer in review".to_string()));
+    }
+    if reviewers.is_empty() {
+        return Err(ContractError::InvalidInput("No reviewers given".to_string()));
+    }
+
+    for reviewer in reviewers {
+        let reviewer = validate_address(deps.api, &reviewer)?;
+        if !REVIEWERS.has(deps.storage, reviewer.as_str()) {
+            return Err(ContractError::InvalidInput(format!("{} does not have the reviewer role", reviewer)));
+        }
+        if reviewer == proposal.recipient {
+            return Err(ContractError::InvalidInput("The grant recipient cannot review it".to_string()));
+        }
+        if !proposal.reviewers.contains(&reviewer) {
+            proposal.reviewers.push(reviewer);
+        }
+    }
+    PROPOSALS.save(deps.storage, &proposal_id.to_string(), &proposal)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "assign_reviewers")
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("reviewers", proposal.reviewers.len().to_string())
+        .add_event(events::reviewers_assigned(&proposal)))
+}
+
+fn execute_submit_review(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    score: u8,
+    comment_hash: String,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let mut proposal = load_proposal(deps.storage, proposal_id)?;
+
+    if !proposal.reviewers.contains(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if proposal.status != ProposalStatus::InReview {
+        return Err(ContractError::InvalidInput("The proposal is no longer in review".to_string()));
+    }
+    if env.block.time.seconds() >= proposal.review_end {
+        return Err(ContractError::InvalidInput("The review period has ended".to_string()));
+    }
+    if score > MAX_REVIEW_SCORE {
+        return Err(ContractError::InvalidInput(format!("Score must be between 0 and {}", MAX_REVIEW_SCORE)));
+    }
+
+    let key = (proposal_id.to_string(), info.sender.to_string());
+    if REVIEWS.has(deps.storage, (&key.0, &key.1)) {
+        return Err(ContractError::AlreadyVoted {});
+    }
+    REVIEWS.save(deps.storage, (&key.0, &key.1), &Review {
+        reviewer: info.sender.clone(),
+        score,
+        comment_hash,
+    })?;
+
+    proposal.review_count += 1;
+    proposal.review_score += score as u32;
+
+    let min_reviews = CONFIG.load(deps.storage)?.min_reviews;
+    let mut res = Response::new()
+        .add_attribute("action", "submit_review")
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("score", score.to_string())
+        .add_event(events::review_submitted(&proposal, &info.sender, score));
+    if proposal.review_count >= min_reviews {
+        proposal.status = ProposalStatus::Open;
+        proposal.voting_end = env.block.time.seconds() + VOTING_PERIOD;
+        res = res.add_event(
+            events::proposal_status_changed(&proposal)
+                .add_attribute(events::ATTR_VOTING_END, proposal.voting_end.to_string()),
+        );
+    }
+    PROPOSALS.save(deps.storage, &proposal_id.to_string(), &proposal)?;
+
+    Ok(res)
+}
+
+/// Fails for delinquent or blocked recipients, and for recipients missing from the allow-list when it is enforced
+fn ensure_recipient_allowed(deps: Deps<CoreumQueries>, recipient: &Addr) -> Result<(), ContractError> {
+    if DELINQUENTS.has(deps.storage, recipient.as_str()) {
+        return Err(ContractError::DelinquentRecipient {});
+    }
+    if RECIPIENT_BLOCKLIST.has(deps.storage, recipient.as_str()) {
+        return Err(ContractError::RecipientBlocked {});
+    }
+    if CONFIG.load(deps.storage)?.allowlist_enabled && !RECIPIENT_ALLOWLIST.has(deps.storage, recipient.as_str()) {
+        return Err(ContractError::RecipientNotAllowed {});
+    }
+    Ok(())
+}
+
+/// Checks the messages of a cw3 proposal and returns how much of the grant denom they send or burn.
+/// Only bank sends and burns move funds, with their recipients checked like grant recipients. Staked
+/// governance tokens and CW20 deposits stay out of reach, and the DAO's smart tokens are administered
+/// through `ProposalAction::AdministerToken`.
+fn check_proposal_msgs(deps: Deps<CoreumQueries>, msgs: &[CosmosMsg<CoreumMsg>]) -> Result<Uint128, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut sent = Uint128::zero();
+    for msg in msgs {
+        let coins = match msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                ensure_recipient_allowed(deps, &validate_address(deps.api, to_address)?)?;
+                amount
+            }
+            CosmosMsg::Bank(BankMsg::Burn { amount }) => amount,
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, funds }) => {
+                let cw20 = config.cw20_tokens.iter().any(|token| token == contract_addr)
+                    || CW20_BALANCES.has(deps.storage, contract_addr)
+                    || from_binary::<Cw20ExecuteMsg>(msg).is_ok();
+                if cw20 {
+                    return Err(ContractError::InvalidInput("CW20 tokens are paid out as CW20 grants".to_string()));
+                }
+                ensure_no_funds(funds)?;
+                continue;
+            }
+            CosmosMsg::Wasm(WasmMsg::Instantiate { funds, .. }) => {
+                ensure_no_funds(funds)?;
+                continue;
+            }
+            CosmosMsg::Wasm(WasmMsg::Migrate { .. })
+            | CosmosMsg::Wasm(WasmMsg::UpdateAdmin { .. })
+            | CosmosMsg::Wasm(WasmMsg::ClearAdmin { .. }) => continue,
+            CosmosMsg::Custom(CoreumMsg::AssetFT(_)) => {
+                return Err(ContractError::InvalidInput(
+                    "Smart tokens are administered with the AdministerToken action".to_string(),
+                ));
+            }
+            _ => return Err(ContractError::InvalidInput("Proposals cannot carry this message".to_string())),
+        };
+        if coins.iter().any(|coin| config.governance_token.as_ref() == Some(&coin.denom)) {
+            return Err(ContractError::InvalidInput("Staked governance tokens cannot be sent".to_string()));
+        }
+        for coin in coins.iter().filter(|coin| coin.denom == config.grant_denom) {
+            sent = sent.checked_add(coin.amount)
+                .map_err(|_| ContractError::InvalidInput("Message amounts overflow".to_string()))?;
+        }
+    }
+    Ok(sent)
+}
+
+fn ensure_no_funds(funds: &[Coin]) -> Result<(), ContractError> {
+    if !funds.is_empty() {
+        return Err(ContractError::InvalidInput("Contract messages cannot carry funds".to_string()));
+    }
+    Ok(())
+}
+
+fn validate_recipient_list_update(api: &dyn Api, update: &RecipientListUpdate) -> Result<(), ContractError> {
+    for address in update.allow.iter().chain(&update.disallow).chain(&update.block).chain(&update.unblock) {
+        validate_address(api, address)?;
+    }
+    Ok(())
+}
+
+/// Applies the update and returns one event per address that was listed or unlisted
+fn apply_recipient_list_update(deps: DepsMut<CoreumQueries>, update: &RecipientListUpdate) -> Result<Vec<Event>, ContractError> {
+    validate_recipient_list_update(deps.api, update)?;
+
+    let mut changes = vec![];
+    for address in &update.allow {
+        RECIPIENT_ALLOWLIST.save(deps.storage, address, &Addr::unchecked(address))?;
+        changes.push(events::recipient_list_changed("allowlist", address, true));
+    }
+    for address in &update.disallow {
+        RECIPIENT_ALLOWLIST.remove(deps.storage, address);
+        changes.push(events::recipient_list_changed("allowlist", address, false));
+    }
+    for address in &update.block {
+        RECIPIENT_BLOCKLIST.save(deps.storage, address, &Addr::unchecked(address))?;
+        changes.push(events::recipient_list_changed("blocklist", address, true));
+    }
+    for address in &update.unblock {
+        RECIPIENT_BLOCKLIST.remove(deps.storage, address);
+        changes.push(events::recipient_list_changed("blocklist", address, false));
+    }
+    Ok(changes)
+}
+
+fn execute_update_recipient_lists(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    update: RecipientListUpdate,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.compliance.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let changes = apply_recipient_list_update(deps, &update)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_recipient_lists")
+        .add_attribute("sender", info.sender.to_string())
+        .add_events(changes))
+}
+
+/// Fails while the guardian has payouts paused
+fn ensure_not_paused(deps: Deps<CoreumQueries>, env: &Env) -> Result<(), ContractError> {
+    match CONFIG.load(deps.storage)?.paused_until {
+        Some(until) if env.block.time.seconds() < until => Err(ContractError::Paused { until }),
+        _ => Ok(()),
+    }
+}
+
+fn execute_pause(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    until: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if config.guardian.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let now = env.block.time.seconds();
+    if until <= now {
+        return Err(ContractError::InvalidInput("A pause must end in the future".to_string()));
+    }
+    if until - now > MAX_PAUSE_PERIOD {
+        return Err(ContractError::InvalidInput(format!(
+            "A pause can last at most {} seconds", MAX_PAUSE_PERIOD
+        )));
+    }
+
+    config.paused_until = Some(until);
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "pause")
+        .add_attribute("until", until.to_string())
+        .add_event(events::config_changed("paused_until", &Some(until))?))
+}
+
+fn execute_unpause(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if config.guardian.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.paused_until = None;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "unpause")
+        .add_event(events::config_changed("paused_until", &None::<u64>)?))
+}
+
+fn validate_veto_council(api: &dyn Api, council: &VetoCouncilInit, timelock: u64) -> Result<VetoCouncil, ContractError> {
+    if timelock == 0 {
+        return Err(ContractError::InvalidInput("A veto council needs a timelock to veto in".to_string()));
+    }
+    match council {
+        VetoCouncilInit::Addresses(addresses) => {
+            if addresses.is_empty() {
+                return Err(ContractError::InvalidInput("The veto council needs at least one member".to_string()));
+            }
+            let mut members: Vec<Addr> = vec![];
+            for address in addresses {
+                let member = validate_address(api, address)?;
+                if members.contains(&member) {
+                    return Err(ContractError::InvalidInput(format!("Duplicate council member {}", member)));
+                }
+                members.push(member);
+            }
+            Ok(VetoCouncil::Addresses(members))
+        }
+        VetoCouncilInit::Group(group) => Ok(VetoCouncil::Group(validate_address(api, group)?)),
+    }
+}
+
+fn is_council_member(deps: Deps<CoreumQueries>, council: &VetoCouncil, address: &Addr) -> StdResult<bool> {
+    match council {
+        VetoCouncil::Addresses(members) => Ok(members.contains(address)),
+        VetoCouncil::Group(group) => {
+            let weight = Cw4Contract::new(group.clone()).is_member(&deps.querier.into_empty(), address, None)?;
+            Ok(weight.is_some_and(|weight| weight > 0))
+        }
+    }
+}
+
+fn execute_veto(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    reason: String,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    match &config.veto_council {
+        Some(council) if is_council_member(deps.as_ref(), council, &info.sender)? => {}
+        _ => return Err(ContractError::Unauthorized {}),
+    }
+
+    let mut proposal = load_proposal(deps.storage, proposal_id)?;
+    if proposal.executed {
+        return Err(ContractError::AlreadyExecuted {});
+    }
+    if matches!(proposal.status, ProposalStatus::Vetoed { .. }) {
+        return Err(ContractError::Vetoed {});
+    }
+
+    let now = env.block.time.seconds();
+    let total = total_weight(deps.as_ref(), Some(proposal.start_height))?;
+    let in_timelock = proposal.status == ProposalStatus::Open
+        && proposal_passed(&proposal, total, config.quorum, now)
+        && now >= proposal.voting_end
+        && now < proposal.voting_end + config.timelock;
+    if !in_timelock {
+        return Err(ContractError::InvalidInput("Only passed proposals in their timelock can be vetoed".to_string()));
+    }
+    if reason.trim().is_empty() {
+        return Err(ContractError::InvalidInput("A veto needs a reason".to_string()));
+    }
+
+    proposal.status = ProposalStatus::Vetoed { by: info.sender.clone(), reason };
+    PROPOSALS.save(deps.storage, &proposal_id.to_string(), &proposal)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "veto")
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("by", info.sender.to_string())
+        .add_event(events::proposal_status_changed(&proposal)))
+}
+
+/// Balance of the grant denom that is not reserved for rounds or scheduled payouts
+fn available_treasury(deps: Deps<CoreumQueries>, env: &Env) -> StdResult<Uint128> {
+    let current_balance = deps.querier.query_balance(&env.contract.address, grant_denom(deps.storage)?)?.amount;
+    let reserved = RESERVED.may_load(deps.storage)?.unwrap_or_default();
+    Ok(current_balance.saturating_sub(reserved))
+}
+
+/// Fails unless the unreserved treasury holds at least `amount` of the grant denom
+fn ensure_treasury(deps: Deps<CoreumQueries>, env: &Env, amount: Uint128) -> Result<(), ContractError> {
+    let available = available_treasury(deps, env)?;
+    if available < amount {
+        return Err(ContractError::InsufficientTreasury { available, required: amount });
+    }
+
+    Ok(())
+}
+
+/// The grant denom's AssetFT definition, or None for a native denom
+fn grant_token(deps: Deps<CoreumQueries>) -> StdResult<Option<assetft::Token>> {
+    let denom = grant_denom(deps.storage)?;
+    if !is_smart_token(&denom) {
+        return Ok(None);
+    }
+    let query = QueryRequest::Custom(CoreumQueries::AssetFT(assetft::Query::Token { denom }));
+    Ok(Some(deps.querier.query::<assetft::TokenResponse>(&query)?.token))
+}
+
+/// Burn rate and send commission are charged to the sender on top of the amount, the issuer pays neither
+fn token_fee_rate(token: &assetft::Token, env: &Env) -> StdResult<Decimal> {
+    if token.issuer == env.contract.address {
+        return Ok(Decimal::zero());
+    }
+    Ok(Decimal::from_str(&token.burn_rate)? + Decimal::from_str(&token.send_commission_rate)?)
+}
+
+/// The fees the treasury pays per unit of the grant denom it sends
+fn payout_fee_rate(deps: Deps<CoreumQueries>, env: &Env) -> StdResult<Decimal> {
+    grant_token(deps)?.map_or(Ok(Decimal::zero()), |token| token_fee_rate(&token, env))
+}
+
+/// What sending `amount` takes out of the treasury, fees included
+fn gross_payout(amount: Uint128, rate: Decimal) -> Uint128 {
+    amount + amount.mul_ceil(rate)
+}
+
+/// What an executed grant that pays out over time still needs set aside at `rate`. Each payout is
+/// counted with its fees.
+fn grant_reservation(proposal: &Proposal, rate: Decimal) -> Uint128 {
+    match &proposal.payout {
+        Payout::LumpSum | Payout::Cw20 { .. } => Uint128::zero(),
+        Payout::Milestones { milestones, .. } => milestones.iter()
+            .filter(|milestone| !matches!(milestone.status, MilestoneStatus::Released | MilestoneStatus::Cancelled))
+            .map(|milestone| gross_payout(milestone.amount, rate))
+            .sum(),
+        Payout::Vesting { terminated_at: Some(_), .. } => Uint128::zero(),
+        Payout::Vesting { claimed, .. } => gross_payout(proposal.amount, rate) - gross_payout(*claimed, rate),
+        Payout::Recurring { stopped: true, .. } => Uint128::zero(),
+        Payout::Recurring { period_amount, periods, claimed, .. } => {
+            gross_payout(*period_amount, rate) * Uint128::from(periods - claimed)
+        }
+    }
+}
+
+/// Releases the part of a grant's reservation it no longer needs, and all of it once the grant is
+/// settled. Never more than was reserved for the grant is released, whatever the fee rate did since.
+fn release_grant_reservation(storage: &mut dyn Storage, proposal: &mut Proposal, rate: Decimal) -> StdResult<()> {
+    let remaining = grant_reservation(proposal, rate).min(proposal.reserved);
+    let released = proposal.reserved - remaining;
+    proposal.reserved = remaining;
+    RESERVED.update(storage, |reserved| -> StdResult<_> { Ok(reserved.saturating_sub(released)) })?;
+    Ok(())
+}
+
+/// Smart tokens can refuse a transfer or charge the sender more than the amount sent. Checks
+/// payouts against the token's features and rates, so that they fail here with a clear error
+/// rather than in the bank module. Release a payout's reservation before checking it.
+fn ensure_payable(deps: Deps<CoreumQueries>, env: &Env, payouts: &[(&Addr, Uint128)]) -> Result<(), ContractError> {
+    let token = match grant_token(deps)? {
+        Some(token) => token,
+        None => return ensure_treasury(deps, env, payouts.iter().map(|(_, amount)| *amount).sum()),
+    };
+    let features = token.features.clone().unwrap_or_default();
+    let rate = token_fee_rate(&token, env)?;
+    let required = payouts.iter().map(|(_, amount)| gross_payout(*amount, rate)).sum();
+    ensure_treasury(deps, env, required)?;
+
+    // Frozen tokens cannot be sent and reserved ones belong to other grants
+    if features.contains(&assetft::FREEZING) {
+        let treasury = smart_token_balance(deps, &env.contract.address, &token.denom)?;
+        let reserved = RESERVED.may_load(deps.storage)?.unwrap_or_default();
+        let spendable = Uint128::from_str(&treasury.balance)?
+            .saturating_sub(Uint128::from_str(&treasury.frozen)?)
+            .saturating_sub(reserved);
+        if spendable < required {
+            return Err(ContractError::TreasuryFrozen { spendable, required });
+        }
+    }
+    // Whitelisting caps what an account other than the issuer may hold
+    if features.contains(&assetft::WHITELISTING) {
+        for (recipient, amount) in payouts.iter().filter(|(recipient, _)| recipient.as_str() != token.issuer) {
+            let holdings = smart_token_balance(deps, recipient, &token.denom)?;
+            let allowed = Uint128::from_str(&holdings.whitelisted)?.saturating_sub(Uint128::from_str(&holdings.balance)?);
+            if allowed < *amount {
+                return Err(ContractError::WhitelistLimit { recipient: recipient.to_string(), allowed });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn smart_token_balance(deps: Deps<CoreumQueries>, account: &Addr, denom: &str) -> StdResult<assetft::BalanceResponse> {
+    let query = assetft::Query::Balance { account: account.to_string(), denom: denom.to_string() };
+    deps.querier.query(&QueryRequest::Custom(CoreumQueries::AssetFT(query)))
+}
+
+/// Records CW20 tokens sent to the DAO. Only accepted tokens can be deposited, so that
+/// everything in `CW20_BALANCES` can be paid out.
+fn execute_receive(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    msg: Cw20ReceiveMsg,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if !config.cw20_tokens.contains(&info.sender) {
+        return Err(ContractError::InvalidInput(format!("{} is not an accepted CW20 token", info.sender)));
+    }
+    CW20_BALANCES.update(deps.storage, info.sender.as_str(), |balance| -> StdResult<_> {
+        Ok(balance.unwrap_or_default() + msg.amount)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "receive")
+        .add_attribute("amount", msg.amount.to_string())
+        .add_event(events::cw20_deposited(&info.sender, &msg.sender, msg.amount)))
+}
+
+fn withdraw_cw20(storage: &mut dyn Storage, contract: &Addr, amount: Uint128) -> Result<(), ContractError> {
+    let available = CW20_BALANCES.may_load(storage, contract.as_str())?.unwrap_or_default();
+    if available < amount {
+        return Err(ContractError::InsufficientTreasury { available, required: amount });
+    }
+    CW20_BALANCES.save(storage, contract.as_str(), &(available - amount))?;
+    Ok(())
+}
+
+fn cw20_payout(contract: &Addr, recipient: &Addr, amount: Uint128) -> StdResult<CosmosMsg<CoreumMsg>> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: contract.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer { recipient: recipient.to_string(), amount })?,
+        funds: vec![],
+    }))
+}
+
+fn grant_denom(storage: &dyn Storage) -> StdResult<String> {
+    Ok(CONFIG.load(storage)?.grant_denom)
+}
+
+fn grant_payout(denom: &str, recipient: &Addr, amount: Uint128) -> CosmosMsg<CoreumMsg> {
+    CosmosMsg::Bank(BankMsg::Send {
+        to_address: recipient.to_string(),
+        amount: vec![Coin {
+            denom: denom.to_string(),
+            amount,
+        }],
+    })
+}
+
+fn validate_round(
+    deps: Deps<CoreumQueries>,
+    budget: Uint128,
+    applications: Vec<RoundApplicationInit>,
+) -> Result<Vec<RoundApplication>, ContractError> {
+    if budget.is_zero() {
+        return Err(ContractError::InvalidInput("Round budget must be positive".to_string()));
+    }
+    if applications.len() < 2 {
+        return Err(ContractError::InvalidInput("A round needs at least two applications".to_string()));
+    }
+    if applications.iter().any(|application| application.amount.is_zero()) {
+        return Err(ContractError::InvalidInput("Application amount must be positive".to_string()));
+    }
+
+    applications.into_iter()
+        .map(|application| {
+            let recipient = validate_address(deps.api, &application.recipient)?;
+            ensure_recipient_allowed(deps, &recipient)?;
+            Ok(RoundApplication {
+                recipient,
+                title: application.title,
+                description: application.description,
+                amount: application.amount,
+            })
+        })
+        .collect()
+}
+
+/// Opens a round passed by the DAO. The budget leaves the spendable treasury until the round is
+/// finalized.
+fn create_round(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    title: String,
+    description: String,
+    method: TallyMethod,
+    budget: Uint128,
+    applications: Vec<RoundApplicationInit>,
+) -> Result<GrantRound, ContractError> {
+    let applications = validate_round(deps.as_ref(), budget, applications)?;
+    let reservation = gross_payout(budget, payout_fee_rate(deps.as_ref(), &env)?);
+    ensure_treasury(deps.as_ref(), &env, reservation)?;
+    RESERVED.update(deps.storage, |reserved| -> StdResult<_> { Ok(reserved + reservation) })?;
+
+    let round_id = ROUND_COUNT.load(deps.storage).unwrap_or_default();
+    ROUND_COUNT.save(deps.storage, &(round_id + 1))?;
+
+    let round = GrantRound {
+        id: round_id,
+        title,
+        description,
+        method,
+        budget,
+        reserved: reservation,
+        applications,
+        start_height: env.block.height,
+        voting_end: env.block.time.seconds() + VOTING_PERIOD,
+        finalized: false,
+        winners: vec![],
+    };
+    ROUNDS.save(deps.storage, &round_id.to_string(), &round)?;
+
+    Ok(round)
+}
+
+fn execute_vote_round(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    round_id: u64,
+    choices: Vec<u32>,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let round = load_round(deps.storage, round_id)?;
+    let member = load_member(deps.as_ref(), &info.sender, Some(round.start_height))?;
+    if env.block.time.seconds() >= round.voting_end {
+        return Err(ContractError::VotingClosed {});
+    }
+
+    let key = (round_id.to_string(), info.sender.to_string());
+    if ROUND_BALLOTS.has(deps.storage, (&key.0, &key.1)) {
+        return Err(ContractError::AlreadyVoted {});
+    }
+
+    if choices.is_empty() {
+        return Err(ContractError::InvalidInput("Ballot must list at least one application".to_string()));
+    }
+    for (i, choice) in choices.iter().enumerate() {
+        if *choice as usize >= round.applications.len() {
+            return Err(ContractError::InvalidInput(format!("Unknown application {}", choice)));
+        }
+        if choices[..i].contains(choice) {
+            return Err(ContractError::InvalidInput(format!("Duplicate application {}", choice)));
+        }
+    }
+
+    let event = events::round_vote_cast(round_id, &info.sender, &choices, member.weight);
+    ROUND_BALLOTS.save(deps.storage, (&key.0, &key.1), &RoundBallot {
+        choices,
+        weight: member.weight,
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "vote_round")
+        .add_attribute("round_id", round_id.to_string())
+        .add_event(event))
+}
+
+fn execute_finalize_round(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    round_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    ensure_not_paused(deps.as_ref(), &env)?;
+    let mut round = load_round(deps.storage, round_id)?;
+
+    if round.finalized {
+        return Err(ContractError::AlreadyExecuted {});
+    }
+    if env.block.time.seconds() < round.voting_end {
+        return Err(ContractError::VotingOpen {});
+    }
+
+    let ballots = ROUND_BALLOTS.prefix(&round_id.to_string())
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .map(|item| item.map(|(_voter, ballot)| ballot))
+        .collect::<StdResult<Vec<RoundBallot>>>()?;
+
+    round.winners = tally_round(&round, &ballots);
+    for winner in &round.winners {
+        ensure_recipient_allowed(deps.as_ref(), &round.applications[*winner as usize].recipient)?;
+    }
+    round.finalized = true;
+    ROUNDS.save(deps.storage, &round_id.to_string(), &round)?;
+
+    // Winners fit in the budget, what they do not use returns to the treasury
+    let winners = round.winners.iter()
+        .map(|winner| {
+            let application = &round.applications[*winner as usize];
+            (&application.recipient, application.amount)
+        })
+        .collect::<Vec<_>>();
+    let total = winners.iter().map(|(_, amount)| *amount).sum::<Uint128>();
+    RESERVED.update(deps.storage, |reserved| -> StdResult<_> { Ok(reserved.saturating_sub(round.reserved)) })?;
+    ensure_payable(deps.as_ref(), &env, &winners)?;
+
+    let denom = grant_denom(deps.storage)?;
+    let payouts = winners.iter().map(|(recipient, amount)| grant_payout(&denom, recipient, *amount));
+
+    Ok(Response::new()
+        .add_messages(payouts)
+        .add_attribute("action", "finalize_round")
+        .add_attribute("round_id", round_id.to_string())
+        .add_attribute("amount", total.to_string())
+        .add_event(
+            events::round_finalized(round_id, events::tally_method_name(&round.method), total)
+                .add_attribute(events::ATTR_WINNERS, events::join(&round.winners)),
+        ))
+}
+
+fn validate_quadratic_round(
+    deps: Deps<CoreumQueries>,
+    matching_pool: Uint128,
+    contribution_cap: Option<Uint128>,
+    applications: Vec<FundingApplicationInit>,
+) -> Result<Vec<FundingApplication>, ContractError> {
+    if matching_pool.is_zero() {
+        return Err(ContractError::InvalidInput("Matching pool must be positive".to_string()));
+    }
+    if contribution_cap.is_some_and(|cap| cap.is_zero()) {
+        return Err(ContractError::InvalidInput("Contribution cap must be positive".to_string()));
+    }
+    if applications.is_empty() {
+        return Err(ContractError::InvalidInput("A round needs at least one application".to_string()));
+    }
+
+    applications.into_iter()
+        .map(|application| {
+            let recipient = validate_address(deps.api, &application.recipient)?;
+            ensure_recipient_allowed(deps, &recipient)?;
+            Ok(FundingApplication {
+                recipient,
+                title: application.title,
+                description: application.description,
+            })
+        })
+        .collect()
+}
+
+/// Opens a quadratic funding round passed by the DAO
+fn create_quadratic_round(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    title: String,
+    description: String,
+    matching_pool: Uint128,
+    contribution_cap: Option<Uint128>,
+    applications: Vec<FundingApplicationInit>,
+) -> Result<QuadraticRound, ContractError> {
+    let applications = validate_quadratic_round(deps.as_ref(), matching_pool, contribution_cap, applications)?;
+
+    // The pool leaves the spendable treasury until the round is finalized
+    let reservation = gross_payout(matching_pool, payout_fee_rate(deps.as_ref(), &env)?);
+    ensure_treasury(deps.as_ref(), &env, reservation)?;
+    RESERVED.update(deps.storage, |reserved| -> StdResult<_> { Ok(reserved + reservation) })?;
+
+    let round_id = QUADRATIC_ROUND_COUNT.load(deps.storage).unwrap_or_default();
+    QUADRATIC_ROUND_COUNT.save(deps.storage, &(round_id + 1))?;
+
+    let round = QuadraticRound {
+        id: round_id,
+        title,
+        description,
+        matching_pool,
+        contribution_cap,
+        contributed: vec![Uint128::zero(); applications.len()],
+        matched: vec![Uint128::zero(); applications.len()],
+        reserved: reservation,
+        applications,
+        round_end: env.block.time.seconds() + VOTING_PERIOD,
+        finalized: false,
+    };
+    QUADRATIC_ROUNDS.save(deps.storage, &round_id.to_string(), &round)?;
+
+    Ok(round)
+}
+
+fn execute_contribute(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    round_id: u64,
+    application: u32,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let mut round = load_quadratic_round(deps.storage, round_id)?;
+
+    if env.block.time.seconds() >= round.round_end {
+        return Err(ContractError::VotingClosed {});
+    }
+    if application as usize >= round.applications.len() {
+        return Err(ContractError::InvalidInput(format!("Unknown application {}", application)));
+    }
+
+    let denom = grant_denom(deps.storage)?;
+    let amount = match info.funds.as_slice() {
+        [coin] if coin.denom == denom && !coin.amount.is_zero() => coin.amount,
+        _ => return Err(ContractError::InvalidInput(format!("Send a single {} coin", denom))),
+    };
+
+    let key = (round_id.to_string(), application.to_string(), info.sender.to_string());
+    CONTRIBUTIONS.update(deps.storage, (&key.0, &key.1, &key.2), |total| -> StdResult<_> {
+        Ok(total.unwrap_or_default() + amount)
+    })?;
+
+    // Contributions are reserved per application with what paying them on costs
+    let rate = payout_fee_rate(deps.as_ref(), &env)?;
+    let contributed = &mut round.contributed[application as usize];
+    let reservation = gross_payout(*contributed + amount, rate) - gross_payout(*contributed, rate);
+    *contributed += amount;
+    round.reserved += reservation;
+    QUADRATIC_ROUNDS.save(deps.storage, &round_id.to_string(), &round)?;
+    RESERVED.update(deps.storage, |reserved| -> StdResult<_> { Ok(reserved + reservation) })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "contribute")
+        .add_attribute("round_id", round_id.to_string())
+        .add_attribute("application", application.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_event(events::contribution_made(round_id, application, &info.sender, amount)))
+}
+
+fn execute_finalize_quadratic_round(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    round_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    ensure_not_paused(deps.as_ref(), &env)?;
+    let mut round = load_quadratic_round(deps.storage, round_id)?;
+
+    if round.finalized {
+        return Err(ContractError::AlreadyExecuted {});
+    }
+    if env.block.time.seconds() < round.round_end {
+        return Err(ContractError::VotingOpen {});
+    }
+
+    round.matched = quadratic_matching(deps.as_ref(), &round)?;
+    for (i, application) in round.applications.iter().enumerate() {
+        if !(round.contributed[i] + round.matched[i]).is_zero() {
+            ensure_recipient_allowed(deps.as_ref(), &application.recipient)?;
+        }
+    }
+    round.finalized = true;
+    QUADRATIC_ROUNDS.save(deps.storage, &round_id.to_string(), &round)?;
+
+    // Contributions and the whole pool are released; whatever was not matched stays in the treasury
+    let total_contributed = round.contributed.iter().sum::<Uint128>();
+    RESERVED.update(deps.storage, |reserved| -> StdResult<_> { Ok(reserved.saturating_sub(round.reserved)) })?;
+
+    let grants = round.applications.iter()
+        .enumerate()
+        .map(|(i, application)| (&application.recipient, round.contributed[i] + round.matched[i]))
+        .filter(|(_, amount)| !amount.is_zero())
+        .collect::<Vec<_>>();
+    ensure_payable(deps.as_ref(), &env, &grants)?;
+    let denom = grant_denom(deps.storage)?;
+    let payouts = grants.iter().map(|(recipient, amount)| grant_payout(&denom, recipient, *amount));
+
+    let matched = round.matched.iter().sum::<Uint128>();
+    Ok(Response::new()
+        .add_messages(payouts)
+        .add_attribute("action", "finalize_quadratic_round")
+        .add_attribute("round_id", round_id.to_string())
+        .add_attribute("matched", matched.to_string())
+        .add_event(
+            events::round_finalized(round_id, events::QUADRATIC_KIND, total_contributed + matched)
+                .add_attribute(events::ATTR_MATCHED, matched.to_string()),
+        ))
+}
+
+// Contributions are scaled up before taking square roots so small donations keep their precision
+const SQRT_SCALE: u128 = 1_000_000;
+
+/// CLR matching: an application's ideal match is (sum of sqrt(contribution))^2 minus the sum of contributions,
+/// counting each contributor only up to the round's cap. When the ideal matches exceed the pool they are scaled
+/// down proportionally.
+fn quadratic_matching(deps: Deps<CoreumQueries>, round: &QuadraticRound) -> StdResult<Vec<Uint128>> {
+    let scale = Uint256::from(SQRT_SCALE) * Uint256::from(SQRT_SCALE);
+
+    let mut ideal = vec![];
+    for i in 0..round.applications.len() {
+        let counted = CONTRIBUTIONS.prefix((&round.id.to_string(), &i.to_string()))
+            .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+            .map(|item| {
+                let (_contributor, amount) = item?;
+                Ok(round.contribution_cap.map_or(amount, |cap| amount.min(cap)))
+            })
+            .collect::<StdResult<Vec<Uint128>>>()?;
+
+        let sqrt_sum = counted.iter()
+            .fold(Uint256::zero(), |sum, amount| sum + isqrt(Uint256::from(*amount) * scale));
+        let counted_total = counted.iter().sum::<Uint128>();
+
+        let squared = sqrt_sum * sqrt_sum / scale;
+        ideal.push(squared.saturating_sub(Uint256::from(counted_total)));
+    }
+
+    let total_ideal = ideal.iter().fold(Uint256::zero(), |sum, m| sum + *m);
+    let pool = Uint256::from(round.matching_pool);
+
+    ideal.into_iter()
+        .map(|m| {
+            let matched = if total_ideal > pool { m * pool / total_ideal } else { m };
+            Ok(Uint128::try_from(matched)?)
+        })
+        .collect()
+}
+
+fn isqrt(n: Uint256) -> Uint256 {
+    if n.is_zero() {
+        return n;
+    }
+    let two = Uint256::from(2u8);
+    let mut x = n;
+    let mut y = (x + Uint256::one()) / two;
+    while y < x {
+        x = y;
+        y = (x + n / x) / two;
+    }
+    x
+}
+
+/// Picks winners one at a time until no remaining application fits in what is left of the budget
+fn tally_round(round: &GrantRound, ballots: &[RoundBallot]) -> Vec<u32> {
+    let mut remaining = round.budget;
+    let mut candidates: Vec<u32> = (0..round.applications.len() as u32).collect();
+    let mut winners = vec![];
+
+    loop {
+        candidates.retain(|c| round.applications[*c as usize].amount <= remaining);
+
+        let winner = match round.method {
+            TallyMethod::Approval => approval_winner(ballots, &candidates),
+            TallyMethod::RankedChoice => instant_runoff_winner(ballots, &candidates),
+        };
+
+        match winner {
+            Some(winner) => {
+                remaining -= round.applications[winner as usize].amount;
+                candidates.retain(|c| *c != winner);
+                winners.push(winner);
+            }
+            None => return winners,
+        }
+    }
+}
+
+/// The candidate approved by the most weight; ties go to the earlier application
+fn approval_winner(ballots: &[RoundBallot], candidates: &[u32]) -> Option<u32> {
+    let mut best: Option<(u32, Uint128)> = None;
+
+    for candidate in candidates {
+        let score = ballots.iter()
+            .filter(|ballot| ballot.choices.contains(candidate))
+            .map(|ballot| ballot.weight)
+            .sum::<Uint128>();
+
+        if !score.is_zero() && best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((*candidate, score));
+        }
+    }
+
+    best.map(|(candidate, _)| candidate)
+}
+
+/// Runs instant-runoff over the candidates. Each ballot counts for its highest ranked candidate still in the
+/// running, and the weakest candidate is eliminated until one holds a majority. Ties eliminate the later application.
+fn instant_runoff_winner(ballots: &[RoundBallot], candidates: &[u32]) -> Option<u32> {
+    let mut running = candidates.to_vec();
+
+    while !running.is_empty() {
+        let mut counts = vec![Uint128::zero(); running.len()];
+        let mut total = Uint128::zero();
+
+        for ballot in ballots {
+            let top = ballot.choices.iter().find_map(|choice| running.iter().position(|c| c == choice));
+            if let Some(position) = top {
+                counts[position] += ballot.weight;
+                total += ballot.weight;
+            }
+        }
+
+        if total.is_zero() {
+            return None;
+        }
+
+        let mut leader = 0;
+        let mut weakest = 0;
+        for (position, count) in counts.iter().enumerate() {
+            if *count > counts[leader] {
+                leader = position;
+            }
+            if *count <= counts[weakest] {
+                weakest = position;
+            }
+        }
+
+        if counts[leader] * Uint128::from(2u8) > total || running.len() == 1 {
+            return Some(running[leader]);
+        }
+        running.remove(weakest);
+    }
+
+    None
+}
+
+
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps<CoreumQueries>, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    match msg {
+        QueryMsg::Threshold {} => query_threshold(deps),
+        QueryMsg::Proposal { proposal_id } => {
+            Ok(to_binary(&proposal_response(deps, &env, load_proposal(deps.storage, proposal_id)?)?)?)
+        }
+        QueryMsg::ListProposals { start_after, limit } => query_list_proposals(deps, env, start_after, limit),
+        QueryMsg::ReverseProposals { start_before, limit } => {
+            query_reverse_proposals(deps, env, start_before, limit)
+        }
+        QueryMsg::Vote { proposal_id, voter } => query_vote(deps, proposal_id, voter),
+        QueryMsg::ListVotes { proposal_id, start_after, limit } => query_list_votes(deps, proposal_id, start_after, limit),
+        QueryMsg::Voter { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let weight = member_weight(deps, &address, None)?.map(cw3_weight);
+            Ok(to_binary(&VoterResponse { weight })?)
+        }
+        QueryMsg::ListVoters { start_after, limit } => query_list_voters(deps, start_after, limit),
+        QueryMsg::GetProposal { proposal_id } => query_proposal(deps, proposal_id),
+        QueryMsg::GetMember { address } => query_member(deps, address),
+        QueryMsg::ListMembers {} => query_all_members(deps),
+        QueryMsg::GetRound { round_id } => query_round(deps, round_id),
+        QueryMsg::ListRounds {} => query_all_rounds(deps),
+        QueryMsg::GetQuadraticRound { round_id } => query_quadratic_round(deps, round_id),
+        QueryMsg::ListQuadraticRounds {} => query_all_quadratic_rounds(deps),
+        QueryMsg::ListContributions { round_id, application } => query_contributions(deps, round_id, application),
+        QueryMsg::QuadraticMatching { round_id } => query_quadratic_matching(deps, round_id),
+        QueryMsg::RecurringSchedule { proposal_id } => query_recurring_schedule(deps, env, proposal_id),
+        QueryMsg::Config {} => Ok(to_binary(&CONFIG.load(deps.storage)?)?),
+        QueryMsg::GovernanceToken {} => {
+            let denom = CONFIG.load(deps.storage)?.governance_token;
+            Ok(to_binary(&GovernanceTokenResponse { denom })?)
+        }
+        QueryMsg::MembershipNft { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            Ok(to_binary(&MembershipNftResponse {
+                class_id: CONFIG.load(deps.storage)?.membership_nft_class,
+                nft: MEMBERSHIP_NFTS.may_load(deps.storage, address.as_str())?,
+            })?)
+        }
+        QueryMsg::Cw20Balances {} => {
+            let balances = CW20_BALANCES
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| item.map(|(address, amount)| Cw20Coin { address, amount }))
+                .collect::<StdResult<Vec<_>>>()?;
+            Ok(to_binary(&Cw20BalancesResponse { balances })?)
+        }
+        QueryMsg::ListCertificates { recipient } => {
+            let recipient = deps.api.addr_validate(&recipient)?;
+            let certificates = CERTIFICATES
+                .prefix(recipient.as_str())
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| item.map(|(_, certificate)| certificate))
+                .collect::<StdResult<Vec<_>>>()?;
+            Ok(to_binary(&CertificateListResponse {
+                class_id: CONFIG.load(deps.storage)?.certificate_class,
+                certificates,
+            })?)
+        }
+        QueryMsg::Claims { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            Ok(to_binary(&CLAIMS.query_claims(deps, &address)?)?)
+        }
+        QueryMsg::GetApplication { application_id } => query_application(deps, application_id),
+        QueryMsg::ListApplications {} => query_all_applications(deps),
+        QueryMsg::ListReviewers {} => query_all_reviewers(deps),
+        QueryMsg::ListReviews { proposal_id } => query_reviews(deps, proposal_id),
+        QueryMsg::GetDelinquency { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            Ok(to_binary(&DELINQUENTS.may_load(deps.storage, address.as_str())?)?)
+        }
+        QueryMsg::ListDelinquents {} => query_all_delinquents(deps),
+        QueryMsg::ListAllowedRecipients {} => query_recipient_list(deps, RECIPIENT_ALLOWLIST),
+        QueryMsg::ListBlockedRecipients {} => query_recipient_list(deps, RECIPIENT_BLOCKLIST),
+    }
+}
+
+fn query_proposal(deps: Deps<CoreumQueries>, proposal_id: u64) -> Result<Binary, ContractError> {
+    let proposal = load_proposal(deps.storage, proposal_id)?;
+    Ok(to_binary(&proposal)?)
+}
+
+/// cw3 weights are u64, larger weights are capped
+fn cw3_weight(weight: Uint128) -> u64 {
+    u64::try_from(weight.u128()).unwrap_or(u64::MAX)
+}
+
+/// Proposals pass when more weight votes yes than no once the quorum has voted, abstentions count
+/// towards the quorum only
+fn query_threshold(deps: Deps<CoreumQueries>) -> Result<Binary, ContractError> {
+    Ok(to_binary(&ThresholdResponse::ThresholdQuorum {
+        threshold: Decimal::percent(50),
+        quorum: CONFIG.load(deps.storage)?.quorum,
+        total_weight: cw3_weight(total_weight(deps, None)?),
+    })?)
+}
+
+/// Whether a proposal has passed. While voting is open only a majority of the whole weight at its
+/// start is final, as the ballots still missing could turn any smaller lead.
+fn proposal_passed(proposal: &Proposal, total_weight: Uint128, quorum: Decimal, now: u64) -> bool {
+    if proposal.votes_for * Uint128::from(2u8) > total_weight {
+        return true;
+    }
+    let voted = proposal.votes_for + proposal.votes_against + proposal.votes_abstain;
+    now >= proposal.voting_end && proposal.votes_for > proposal.votes_against && voted >= total_weight.mul_ceil(quorum)
+}
+
+/// Where a proposal stands in cw3 terms
+fn cw3_status(proposal: &Proposal, now: u64, config: &Config, total_weight: Uint128) -> Status {
+    let passed = proposal_passed(proposal, total_weight, config.quorum, now);
+    match proposal.status {
+        _ if proposal.executed => Status::Executed,
+        ProposalStatus::InReview if now >= proposal.review_end => Status::Rejected,
+        ProposalStatus::InReview => Status::Pending,
+        // Only executed grants can be clawed back
+        ProposalStatus::ClawedBack => Status::Executed,
+        // cw3 has no vetoed status, and a veto is as final as a failed vote
+        ProposalStatus::Vetoed { .. } | ProposalStatus::Rejected => Status::Rejected,
+        // Without a timelock a majority of the whole weight can execute before voting ends
+        ProposalStatus::Open if passed && (config.timelock == 0 || now >= proposal.voting_end) => Status::Passed,
+        ProposalStatus::Open if now >= proposal.voting_end => Status::Rejected,
+        ProposalStatus::Open => Status::Open,
+    }
+}
+
+fn proposal_response(deps: Deps<CoreumQueries>, env: &Env, proposal: Proposal) -> Result<ProposalResponse<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let total = total_weight(deps, Some(proposal.start_height))?;
+
+    let mut msgs = vec![];
+    if proposal.action.is_none() && !proposal.amount.is_zero() {
+        match &proposal.payout {
+            Payout::LumpSum => msgs.push(grant_payout(&grant_denom(deps.storage)?, &proposal.recipient, proposal.amount)),
+            Payout::Cw20 { contract } => msgs.push(cw20_payout(contract, &proposal.recipient, proposal.amount)?),
+            _ => {}
+        }
+    }
+    msgs.extend(proposal.msgs.iter().cloned());
+
+    Ok(ProposalResponse {
+        id: proposal.id,
+        status: cw3_status(&proposal, env.block.time.seconds(), &config, total),
+        expires: match proposal.status {
+            ProposalStatus::InReview => Expiration::AtTime(Timestamp::from_seconds(proposal.review_end)),
+            _ => Expiration::AtTime(Timestamp::from_seconds(proposal.voting_end)),
+        },
+        threshold: ThresholdResponse::ThresholdQuorum {
+            threshold: Decimal::percent(50),
+            quorum: config.quorum,
+            total_weight: cw3_weight(total),
+        },
+        title: proposal.title,
+        description: proposal.description,
+        msgs,
+    })
+}
+
+fn query_list_proposals(deps: Deps<CoreumQueries>, env: Env, start_after: Option<u64>, limit: Option<u32>) -> Result<Binary, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map_or(0, |id| id.saturating_add(1));
+    let count = PROPOSAL_COUNT.load(deps.storage)?;
+
+    // Proposal ids are handed out in sequence, so every id below the count exists
+    let proposals = (start..count)
+        .take(limit)
+        .map(|id| proposal_response(deps, &env, load_proposal(deps.storage, id)?))
+        .collect::<Result<Vec<ProposalResponse<CoreumMsg>>, ContractError>>()?;
+    Ok(to_binary(&ProposalListResponse { proposals })?)
+}
+
+fn query_reverse_proposals(
+    deps: Deps<CoreumQueries>,
+    env: Env,
+    start_before: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Binary, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let count = PROPOSAL_COUNT.load(deps.storage)?;
+    let end = start_before.map_or(count, |id| id.min(count));
+
+    let proposals = (0..end)
+        .rev()
+        .take(limit)
+        .map(|id| proposal_response(deps, &env, load_proposal(deps.storage, id)?))
+        .collect::<Result<Vec<ProposalResponse<CoreumMsg>>, ContractError>>()?;
+    Ok(to_binary(&ProposalListResponse { proposals })?)
+}
+
+fn query_vote(deps: Deps<CoreumQueries>, proposal_id: u64, voter: String) -> Result<Binary, ContractError> {
+    let voter = deps.api.addr_validate(&voter)?;
+    let vote = BALLOTS.may_load(deps.storage, (&proposal_id.to_string(), voter.as_str()))?
+        .map(|ballot| VoteInfo {
+            proposal_id,
+            voter: voter.to_string(),
+            vote: ballot.vote,
+            weight: cw3_weight(ballot.weight),
+        });
+    Ok(to_binary(&VoteResponse { vote })?)
+}
+
+fn query_list_votes(
+    deps: Deps<CoreumQueries>,
+    proposal_id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Binary, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let votes = BALLOTS.prefix(&proposal_id.to_string())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (voter, ballot) = item?;
+            Ok(VoteInfo { proposal_id, voter, vote: ballot.vote, weight: cw3_weight(ballot.weight) })
+        })
+        .collect::<StdResult<Vec<VoteInfo>>>()?;
+    Ok(to_binary(&VoteListResponse { votes })?)
+}
+
+fn query_list_voters(deps: Deps<CoreumQueries>, start_after: Option<String>, limit: Option<u32>) -> Result<Binary, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let config = CONFIG.load(deps.storage)?;
+    let voters = match (config.group, config.governance_token) {
+        (Some(group), _) => Cw4Contract::new(group).list_members(&deps.querier.into_empty(), start_after, Some(limit as u32))?
+            .into_iter()
+            .map(|member| VoterDetail { addr: member.addr, weight: member.weight })
+            .collect(),
+        (None, Some(_)) if config.staking_mode == StakingMode::Replace => {
+            let start = start_after.as_deref().map(Bound::exclusive);
+            STAKES.range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|item| {
+                    let (addr, stake) = item?;
+                    Ok(VoterDetail { addr, weight: cw3_weight(stake) })
+                })
+                .collect::<StdResult<Vec<VoterDetail>>>()?
+        }
+        (None, _) => {
+            let start = start_after.as_deref().map(Bound::exclusive);
+            MEMBERS.range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|item| {
+                    let (addr, member) = item?;
+                    let stake = STAKES.may_load(deps.storage, &addr)?.unwrap_or_default();
+                    Ok(VoterDetail { addr, weight: cw3_weight(member.weight + stake) })
+                })
+                .collect::<StdResult<Vec<VoterDetail>>>()?
+        }
+    };
+    Ok(to_binary(&VoterListResponse { voters })?)
+}
+
+fn query_member(deps: Deps<CoreumQueries>, address: String) -> Result<Binary, ContractError> {
+    let address = deps.api.addr_validate(&address)?;
+    let member = load_member(deps, &address, None)?;
+    Ok(to_binary(&member)?)
+}
+
+fn query_all_members(deps: Deps<CoreumQueries>) -> Result<Binary, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let members = match (config.group, config.governance_token) {
+        (Some(group), _) => {
+            // The group pages its member list, collect every page
+            let group = Cw4Contract::new(group);
+            let mut members: Vec<Member> = vec![];
+            loop {
+                let start_after = members.last().map(|member| member.address.to_string());
+                let page = group.list_members(&deps.querier.into_empty(), start_after, Some(MAX_LIMIT))?;
+                if page.is_empty() {
+                    break;
+                }
+                members.extend(page.into_iter().map(|member| Member {
+                    address: Addr::unchecked(member.addr),
+                    weight: Uint128::from(member.weight),
+                }));
+            }
+            members
+        }
+        (None, Some(_)) if config.staking_mode == StakingMode::Replace => STAKES.range(deps.storage, None, None, Order::Ascending)
+            .map(|item| {
+                let (address, weight) = item?;
+                Ok(Member { address: Addr::unchecked(address), weight })
+            })
+            .collect::<StdResult<Vec<Member>>>()?,
+        (None, _) => MEMBERS.range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+            .map(|item| {
+                let (key, mut member) = item?;
+                // Supplementing stakes count towards the weight
+                member.weight += STAKES.may_load(deps.storage, &key)?.unwrap_or_default();
+                Ok(member)
+            })
+            .collect::<StdResult<Vec<Member>>>()?,
+    };
+    Ok(to_binary(&members)?)
+}
+
+fn query_round(deps: Deps<CoreumQueries>, round_id: u64) -> Result<Binary, ContractError> {
+    let round = load_round(deps.storage, round_id)?;
+    Ok(to_binary(&round)?)
+}
+
+fn query_all_rounds(deps: Deps<CoreumQueries>) -> Result<Binary, ContractError> {
+    let rounds = ROUNDS.range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .map(|item| {
+            let (_key, round) = item?;
+            Ok(round)
+        })
+        .collect::<StdResult<Vec<GrantRound>>>()?;
+    Ok(to_binary(&rounds)?)
+}
+
+fn query_quadratic_round(deps: Deps<CoreumQueries>, round_id: u64) -> Result<Binary, ContractError> {
+    let round = load_quadratic_round(deps.storage, round_id)?;
+    Ok(to_binary(&round)?)
+}
+
+fn query_all_quadratic_rounds(deps: Deps<CoreumQueries>) -> Result<Binary, ContractError> {
+    let rounds = QUADRATIC_ROUNDS.range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .map(|item| {
+            let (_key, round) = item?;
+            Ok(round)
+        })
+        .collect::<StdResult<Vec<QuadraticRound>>>()?;
+    Ok(to_binary(&rounds)?)
+}
+
+fn query_contributions(deps: Deps<CoreumQueries>, round_id: u64, application: u32) -> Result<Binary, ContractError> {
+    let contributions = CONTRIBUTIONS.prefix((&round_id.to_string(), &application.to_string()))
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .map(|item| {
+            let (contributor, amount) = item?;
+            Ok(Contribution { contributor: Addr::unchecked(contributor), amount })
+        })
+        .collect::<StdResult<Vec<Contribution>>>()?;
+    Ok(to_binary(&contributions)?)
+}
+
+/// Final payouts once the round is finalized, otherwise the matching as it would be computed now
+fn query_quadratic_matching(deps: Deps<CoreumQueries>, round_id: u64) -> Result<Binary, ContractError> {
+    let round = load_quadratic_round(deps.storage, round_id)?;
+    let matched = if round.finalized { round.matched.clone() } else { quadratic_matching(deps, &round)? };
+
+    let matching = round.contributed.iter().zip(matched)
+        .enumerate()
+        .map(|(i, (contributed, matched))| MatchingResponse {
+            application: i as u32,
+            contributed: *contributed,
+            matched,
+        })
+        .collect::<Vec<MatchingResponse>>();
+    Ok(to_binary(&matching)?)
+}
+
+fn query_recurring_schedule(deps: Deps<CoreumQueries>, env: Env, proposal_id: u64) -> Result<Binary, ContractError> {
+    let proposal = load_proposal(deps.storage, proposal_id)?;
+
+    match proposal.payout {
+        Payout::Recurring { period_amount, interval, periods, start, claimed, stopped } => {
+            let periods_remaining = if stopped { 0 } else { periods - claimed };
+            let next_due = match start {
+                Some(start) if periods_remaining > 0 => Some(start + interval * (claimed as u64 + 1)),
+                _ => None,
+            };
+            Ok(to_binary(&RecurringResponse {
+                period_amount,
+                interval,
+                next_due,
+                periods_remaining,
+                periods_due: periods_due(&proposal, env.block.time.seconds()) - claimed,
+            })?)
+        }
+        _ => Err(ContractError::InvalidInput(format!("Proposal {} is not a recurring grant", proposal_id))),
+    }
+}
+
+fn query_application(deps: Deps<CoreumQueries>, application_id: u64) -> Result<Binary, ContractError> {
+    let application = load_application(deps.storage, application_id)?;
+    Ok(to_binary(&application)?)
+}
+
+fn query_all_applications(deps: Deps<CoreumQueries>) -> Result<Binary, ContractError> {
+    let applications = APPLICATIONS.range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .map(|item| {
+            let (_key, application) = item?;
+            Ok(application)
+        })
+        .collect::<StdResult<Vec<GrantApplication>>>()?;
+    Ok(to_binary(&applications)?)
+}
+
+fn query_all_reviewers(deps: Deps<CoreumQueries>) -> Result<Binary, ContractError> {
+    let reviewers = REVIEWERS.range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .map(|item| {
+            let (_key, reviewer) = item?;
+            Ok(reviewer)
+        })
+        .collect::<StdResult<Vec<Addr>>>()?;
+    Ok(to_binary(&reviewers)?)
+}
+
+fn query_reviews(deps: Deps<CoreumQueries>, proposal_id: u64) -> Result<Binary, ContractError> {
+    let reviews = REVIEWS.prefix(&proposal_id.to_string())
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .map(|item| {
+            let (_key, review) = item?;
+            Ok(review)
+        })
+        .collect::<StdResult<Vec<Review>>>()?;
+    Ok(to_binary(&reviews)?)
+}
+
+fn query_all_delinquents(deps: Deps<CoreumQueries>) -> Result<Binary, ContractError> {
+    let delinquents = DELINQUENTS.range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .map(|item| {
+            let (_key, delinquency) = item?;
+            Ok(delinquency)
+        })
+        .collect::<StdResult<Vec<Delinquency>>>()?;
+    Ok(to_binary(&delinquents)?)
+}
+
+fn query_recipient_list(deps: Deps<CoreumQueries>, list: Map<&str, Addr>) -> Result<Binary, ContractError> {
+    let addresses = list.range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .map(|item| {
+            let (_key, address) = item?;
+            Ok(address)
+        })
+        .collect::<StdResult<Vec<Addr>>>()?;
+    Ok(to_binary(&addresses)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{coins, from_binary, Addr, ContractResult, OwnedDeps, SystemError, SystemResult, Uint128};
+    use crate::msg::{MemberInit, MilestoneInit, NftClassInit};
+    use cw_multi_test::{next_block, AppResponse, BasicApp, BasicAppBuilder, Contract, ContractWrapper, Executor};
+    use std::marker::PhantomData;
+
+    const GOVERNANCE_TOKEN: &str = "ugov-devcore1issuer";
+    const GRANT_TOKEN: &str = "ugrant-devcore1issuer";
+    // Issued by the DAO
+    const DAO_TOKEN: &str = "udao-cosmos2contract";
+
+    /// Answers the Coreum queries the contract makes, `GOVERNANCE_TOKEN` is the only smart token
+    fn coreum_querier(query: &CoreumQueries) -> SystemResult<ContractResult<Binary>> {
+        match query {
+            CoreumQueries::AssetFT(assetft::Query::Token { denom }) if denom == GOVERNANCE_TOKEN => {
+                let token = assetft::Token {
+                    denom: denom.clone(),
+                    issuer: "devcore1issuer".to_string(),
+                    symbol: "GOV".to_string(),
+                    subunit: "ugov".to_string(),
+                    precision: 6,
+                    description: None,
+                    features: Some(vec![assetft::MINTING]),
+                    burn_rate: "0".to_string(),
+                    send_commission_rate: "0".to_string(),
+                    version: 1,
+                };
+                SystemResult::Ok(to_binary(&assetft::TokenResponse { token }).into())
+            }
+            CoreumQueries::AssetFT(assetft::Query::Token { denom }) if denom == GRANT_TOKEN => {
+                let token = assetft::Token {
+                    denom: denom.clone(),
+                    issuer: "devcore1issuer".to_string(),
+                    symbol: "GRANT".to_string(),
+                    subunit: "ugrant".to_string(),
+                    precision: 6,
+                    description: None,
+                    features: Some(vec![assetft::FREEZING, assetft::WHITELISTING]),
+                    burn_rate: "0.1".to_string(),
+                    send_commission_rate: "0.05".to_string(),
+                    version: 1,
+                };
+                SystemResult::Ok(to_binary(&assetft::TokenResponse { token }).into())
+            }
+            CoreumQueries::AssetFT(assetft::Query::Token { denom }) if denom == DAO_TOKEN => {
+                let token = assetft::Token {
+                    denom: denom.clone(),
+                    issuer: MOCK_CONTRACT_ADDR.to_string(),
+                    symbol: "DAO".to_string(),
+                    subunit: "udao".to_string(),
+                    precision: 6,
+                    description: None,
+                    features: Some(vec![assetft::MINTING, assetft::FREEZING]),
+                    burn_rate: "0".to_string(),
+                    send_commission_rate: "0".to_string(),
+                    version: 1,
+                };
+                SystemResult::Ok(to_binary(&assetft::TokenResponse { token }).into())
+            }
+            // The treasury has 100 frozen, the grantee may hold 300 and holds 50
+            CoreumQueries::AssetFT(assetft::Query::Balance { account, denom }) if denom == GRANT_TOKEN => {
+                let (balance, whitelisted, frozen) = match account.as_str() {
+                    MOCK_CONTRACT_ADDR => ("1000", "0", "100"),
+                    _ => ("50", "300", "0"),
+                };
+                let balance = assetft::BalanceResponse {
+                    balance: balance.to_string(),
+                    whitelisted: whitelisted.to_string(),
+                    frozen: frozen.to_string(),
+                    locked: "0".to_string(),
+                };
+                SystemResult::Ok(to_binary(&balance).into())
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: format!("{:?}", query) }),
+        }
+    }
+
+    fn mock_dependencies_with_balance(
+        balance: &[Coin],
+    ) -> OwnedDeps<MockStorage, MockApi, MockQuerier<CoreumQueries>, CoreumQueries> {
+        OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: MockQuerier::new(&[(MOCK_CONTRACT_ADDR, balance)]).with_custom_handler(coreum_querier),
+            custom_query_type: PhantomData,
+        }
+    }
+
+    fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, MockQuerier<CoreumQueries>, CoreumQueries> {
+        mock_dependencies_with_balance(&[])
+    }
+
+    /// The fields of `ExecuteMsg::Propose`, so tests only spell out the ones they care about
+    #[derive(Clone)]
+    struct Propose {
+        title: String,
+        description: String,
+        msgs: Vec<CosmosMsg<CoreumMsg>>,
+        latest: Option<Expiration>,
+        recipient: Option<String>,
+        amount: Option<Uint128>,
+        schedule: Option<PayoutSchedule>,
+        action: Option<ProposalAction>,
+        url: Option<String>,
+        content_hash: Option<String>,
+    }
+
+    impl From<Propose> for ExecuteMsg {
+        fn from(propose: Propose) -> Self {
+            let Propose { title, description, msgs, latest, recipient, amount, schedule, action, url, content_hash } = propose;
+            ExecuteMsg::Propose { title, description, msgs, latest, recipient, amount, schedule, action, url, content_hash }
+        }
+    }
+
+    /// A lump sum grant, or a plain proposal without recipient and amount
+    fn propose_msg(title: &str, recipient: Option<&str>, amount: Option<u128>) -> Propose {
+        Propose {
+            title: title.to_string(),
+            description: format!("{} for the DAO", title),
+            msgs: vec![],
+            latest: None,
+            recipient: recipient.map(String::from),
+            amount: amount.map(Uint128::from),
+            schedule: None,
+            action: None,
+            url: None,
+            content_hash: None,
+        }
+    }
+
+
+    #[test]
+    fn proper_instantiation() {
+        let mut deps = mock_dependencies();
+        
+        let members = vec![
+            MemberInit {
+                address: "devcore1member_a".to_string(),
+                weight: Uint128::from(10_u128),
+            },
+            MemberInit {
+                address: "devcore1member_b".to_string(),
+                weight: Uint128::from(20_u128),
+
+            },
+        ];
+
+        let msg = InstantiateMsg { members, ..Default::default() };
+        let info = mock_info("creator", &[]);
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+    }
+
+    #[test]
+    fn proposal_creation() {
+        let mut deps = mock_dependencies();
+
+        let members = vec![
+            MemberInit {
+                address: "devcore1member_a".to_string(),
+                weight: Uint128::from(10_u128),
+            },
+        ];
+
+        let msg = InstantiateMsg { members, ..Default::default() };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Propose
+        let info = mock_info("devcore1member_a", &[]);
+        let msg = ExecuteMsg::from(propose_msg("Test Proposal", Some("devcore1recipient"), Some(100)));
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // Each proposal gets its own ID
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ListProposals { start_after: None, limit: None }).unwrap();
+        let list: ProposalListResponse = from_binary(&res).unwrap();
+        assert_eq!(vec![0, 1], list.proposals.iter().map(|p| p.id).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn addresses_are_validated() {
+        let mut deps = mock_dependencies();
+        let member = |address: &str, weight: u128| MemberInit {
+            address: address.to_string(),
+            weight: Uint128::from(weight),
+        };
+
+        let invalid = vec![
+            vec![member("cosmos1member_a", 10)],
+            vec![member("devcore1member_a", 0)],
+            vec![member("devcore1member_a", 10), member("devcore1member_a", 5)],
+        ];
+        for members in invalid {
+            let mut deps = mock_dependencies();
+            let msg = InstantiateMsg { members, ..Default::default() };
+            let err = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+            assert!(matches!(err, ContractError::InvalidInput(_)));
+        }
+
+        let msg = InstantiateMsg { members: vec![member("devcore1member_a", 10)], ..Default::default() };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::from(propose_msg("Test Proposal", Some("osmo1recipient"), Some(100)));
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+    }
+
+    #[test]
+    fn proposal_content_is_limited() {
+        let mut deps = mock_dependencies();
+        let members = vec![MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) }];
+        let content_limits = ContentLimits { max_title_length: 16, ..Default::default() };
+        let msg = InstantiateMsg { members, content_limits: Some(content_limits), ..Default::default() };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let propose = |title: &str, url: Option<&str>, content_hash: Option<&str>| ExecuteMsg::from(Propose {
+            url: url.map(String::from),
+            content_hash: content_hash.map(String::from),
+            ..propose_msg(title, None, Some(100))
+        });
+        let info = mock_info("devcore1member_a", &[]);
+        let hash = "9F86D081884C7D659A2FEAA0C55AD015A3BF4F1B2B0B822CD15D6C15B0F00A08";
+
+        for msg in [
+            propose("A title well over sixteen characters", None, None),
+            propose("   ", None, None),
+            propose("Docs", Some("ftp://example.com/grant"), None),
+            propose("Docs", Some("https://example.com/my grant"), None),
+            propose("Docs", None, Some("not-a-hash")),
+        ] {
+            let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+            assert!(matches!(err, ContractError::InvalidInput(_)));
+        }
+
+        execute(deps.as_mut(), mock_env(), info, propose("Docs", Some("ipfs://bafy/grant.md"), Some(hash))).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetProposal { proposal_id: 0 }).unwrap();
+        let proposal: Proposal = from_binary(&res).unwrap();
+        assert_eq!(Some("ipfs://bafy/grant.md".to_string()), proposal.url);
+        assert_eq!(Some(hash.to_ascii_lowercase()), proposal.content_hash);
+    }
+
+    #[test]
+    fn vote_for_proposal() {
+        let mut deps = mock_dependencies();
+
+        let members = vec![
+            MemberInit {
+                address: "devcore1member_a".to_string(),
+                weight: Uint128::from(10_u128),
+            },
+        ];
+        let msg = InstantiateMsg { members, ..Default::default() };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Propose
+        let info = mock_info("devcore1member_a", &[]);
+        let proposal_msg = ExecuteMsg::from(propose_msg("Some Title", Some("devcore1recipient"), Some(100)));
+        execute(deps.as_mut(), mock_env(), info.clone(), proposal_msg).unwrap();
+
+        let vote_msg = ExecuteMsg::Vote {
+            proposal_id: 0,
+            vote: Vote::Yes,
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), vote_msg.clone()).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), vote_msg).unwrap_err();
+        assert!(matches!(err, ContractError::AlreadyVoted {}));
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1outsider", &[]), ExecuteMsg::Vote {
+            proposal_id: 0,
+            vote: Vote::No,
+        }).unwrap_err();
+        assert!(matches!(err, ContractError::NotMember { address } if address == "devcore1outsider"));
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::Vote { proposal_id: 7, vote: Vote::Yes })
+            .unwrap_err();
+        assert!(matches!(err, ContractError::ProposalNotFound { id: 7 }));
+        let err = query(deps.as_ref(), mock_env(), QueryMsg::GetProposal { proposal_id: 7 }).unwrap_err();
+        assert!(matches!(err, ContractError::ProposalNotFound { id: 7 }));
+
+        // A second proposal nobody votes on before its voting period ends
+        execute(deps.as_mut(), mock_env(), info.clone(), propose_msg("Late", None, Some(100)).into()).unwrap();
+        let err = execute(deps.as_mut(), env_at(VOTING_PERIOD), info.clone(), ExecuteMsg::Vote { proposal_id: 1, vote: Vote::Yes })
+            .unwrap_err();
+        assert!(matches!(err, ContractError::VotingClosed {}));
+        let err = execute(deps.as_mut(), env_at(VOTING_PERIOD), info, ExecuteMsg::Execute { proposal_id: 1 }).unwrap_err();
+        assert!(matches!(err, ContractError::NotPassed {}));
+    }
+
+
+    #[test]
+    fn execute_proposal() {
+        let mut deps = mock_dependencies_with_balance(&coins(1000, "udevcore"));
+
+        let members = vec![
+            MemberInit {
+                address: "devcore1member_a".to_string(),
+                weight: Uint128::from(10_u128),
+            },
+        ];
+        let msg = InstantiateMsg { members, ..Default::default() };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("devcore1member_a", &[]);
+        let proposal_msg = ExecuteMsg::from(propose_msg("Another Title", Some("devcore1recipient"), Some(100)));
+        execute(deps.as_mut(), mock_env(), info.clone(), proposal_msg).unwrap();
+
+        let vote_msg = ExecuteMsg::Vote {
+            proposal_id: 0,
+            vote: Vote::Yes,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), vote_msg).unwrap();
+
+        let exec_msg = ExecuteMsg::Execute { proposal_id: 0 };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), exec_msg.clone()).unwrap();
+        assert_eq!(1, res.messages.len());
+
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), exec_msg).unwrap_err();
+        assert!(matches!(err, ContractError::AlreadyExecuted {}));
+
+        // The mocked treasury holds 1000
+        let big_grant = ExecuteMsg::from(propose_msg("Too big", None, Some(2000)));
+        let err = pass_proposal(deps.as_mut(), mock_env(), big_grant).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InsufficientTreasury { available, required }
+                if available == Uint128::from(1000_u128) && required == Uint128::from(2000_u128)
+        ));
+    }
+
+    fn setup_milestone_grant(deps: DepsMut<CoreumQueries>, reviewer: Option<&str>) {
+        let members = vec![
+            MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) },
+            MemberInit { address: "devcore1member_b".to_string(), weight: Uint128::from(20_u128) },
+        ];
+        let mut deps = deps;
+        instantiate(deps.branch(), mock_env(), mock_info("creator", &[]), InstantiateMsg { members, ..Default::default() }).unwrap();
+
+        let msg = ExecuteMsg::from(Propose {
+            schedule: Some(PayoutSchedule::Milestones {
+                reviewer: reviewer.map(String::from),
+                milestones: vec![
+                    MilestoneInit { description: "Indexer".to_string(), amount: Uint128::from(300_u128) },
+                    MilestoneInit { description: "Frontend".to_string(), amount: Uint128::from(200_u128) },
+                ],
+            }),
+            ..propose_msg("Block explorer", Some("devcore1grantee"), None)
+        });
+        execute(deps.branch(), mock_env(), mock_info("devcore1member_a", &[]), msg).unwrap();
+
+        let vote_msg = ExecuteMsg::Vote { proposal_id: 0, vote: Vote::Yes };
+        execute(deps.branch(), mock_env(), mock_info("devcore1member_b", &[]), vote_msg).unwrap();
+
+        let res = execute(deps.branch(), mock_env(), mock_info("devcore1member_a", &[]), ExecuteMsg::Execute { proposal_id: 0 })
+            .unwrap();
+        assert_eq!(0, res.messages.len());
+        assert_eq!(Uint128::from(500_u128), RESERVED.load(deps.storage).unwrap());
+    }
+
+    fn milestone_msg(deps: DepsMut<CoreumQueries>, sender: &str, msg: ExecuteMsg) -> Result<Response<CoreumMsg>, ContractError> {
+        execute(deps, mock_env(), mock_info(sender, &[]), msg)
+    }
+
+    #[test]
+    fn milestones_are_released_after_review() {
+        let mut deps = mock_dependencies_with_balance(&coins(1000, "udevcore"));
+        setup_milestone_grant(deps.as_mut(), Some("devcore1reviewer"));
+
+        let release = ExecuteMsg::ReleaseMilestone { proposal_id: 0, index: 0 };
+        let err = milestone_msg(deps.as_mut(), "devcore1grantee", release.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+
+        let submit = ExecuteMsg::SubmitMilestone {
+            proposal_id: 0,
+            index: 0,
+            deliverable: "https://github.com/grantee/explorer/releases/v1".to_string(),
+        };
+        let err = milestone_msg(deps.as_mut(), "devcore1member_a", submit.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        milestone_msg(deps.as_mut(), "devcore1grantee", submit).unwrap();
+        milestone_msg(deps.as_mut(), "devcore1reviewer", ExecuteMsg::ApproveMilestone { proposal_id: 0, index: 0 }).unwrap();
+
+        let res = milestone_msg(deps.as_mut(), "anyone", release.clone()).unwrap();
+        assert_eq!(res.messages[0].msg, grant_payout(GRANT_DENOM, &Addr::unchecked("devcore1grantee"), Uint128::from(300_u128)));
+        assert_eq!(Uint128::from(200_u128), RESERVED.load(&deps.storage).unwrap());
+        let err = milestone_msg(deps.as_mut(), "anyone", release).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+
+        // Cancelling the second milestone hands its reservation back to the treasury
+        milestone_msg(deps.as_mut(), "devcore1reviewer", ExecuteMsg::CancelMilestone { proposal_id: 0, index: 1 }).unwrap();
+        assert_eq!(Uint128::zero(), RESERVED.load(&deps.storage).unwrap());
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetProposal { proposal_id: 0 }).unwrap();
+        let proposal: Proposal = from_binary(&res).unwrap();
+        match proposal.payout {
+            Payout::Milestones { milestones, .. } => {
+                assert_eq!(MilestoneStatus::Released, milestones[0].status);
+                assert_eq!(MilestoneStatus::Cancelled, milestones[1].status);
+            }
+            _ => panic!("expected milestones"),
+        }
+    }
+
+    #[test]
+    fn milestones_without_reviewer_need_member_majority() {
+        let mut deps = mock_dependencies_with_balance(&coins(1000, "udevcore"));
+        setup_milestone_grant(deps.as_mut(), None);
+
+        let submit = ExecuteMsg::SubmitMilestone { proposal_id: 0, index: 1, deliverable: "ipfs://frontend".to_string() };
+        milestone_msg(deps.as_mut(), "devcore1grantee", submit).unwrap();
+
+        // Members who join after the submission neither approve nor raise the bar
+        let height = mock_env().block.height;
+        let member_c = Member { address: Addr::unchecked("devcore1member_c"), weight: Uint128::from(100_u128) };
+        MEMBERS.save(&mut deps.storage, "devcore1member_c", &member_c, height).unwrap();
+        TOTAL_WEIGHT.save(&mut deps.storage, &Uint128::from(130_u128), height).unwrap();
+        let approve = ExecuteMsg::ApproveMilestone { proposal_id: 0, index: 1 };
+        let err = milestone_msg(deps.as_mut(), "devcore1member_c", approve.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NotMember { .. }));
+
+        milestone_msg(deps.as_mut(), "devcore1member_a", approve.clone()).unwrap();
+        let release = ExecuteMsg::ReleaseMilestone { proposal_id: 0, index: 1 };
+        milestone_msg(deps.as_mut(), "anyone", release.clone()).unwrap_err();
+
+        milestone_msg(deps.as_mut(), "devcore1member_b", approve).unwrap();
+        let res = milestone_msg(deps.as_mut(), "anyone", release).unwrap();
+        assert_eq!(1, res.messages.len());
+
+        let err = milestone_msg(deps.as_mut(), "devcore1member_a", ExecuteMsg::CancelMilestone { proposal_id: 0, index: 0 })
+            .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+    }
+
+    fn env_at(seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(seconds);
+        env
+    }
+
+    /// member_a proposes and every member votes yes, so the proposal can be executed straight away
+    fn pass_proposal(deps: DepsMut<CoreumQueries>, env: Env, msg: ExecuteMsg) -> Result<Response<CoreumMsg>, ContractError> {
+        let mut deps = deps;
+        let proposal_id = PROPOSAL_COUNT.load(deps.storage).unwrap();
+        execute(deps.branch(), env.clone(), mock_info("devcore1member_a", &[]), msg).unwrap();
+        let voters = MEMBERS.keys(deps.storage, None, None, Order::Ascending).collect::<StdResult<Vec<_>>>().unwrap();
+        for voter in voters {
+            execute(deps.branch(), env.clone(), mock_info(&voter, &[]), ExecuteMsg::Vote { proposal_id, vote: Vote::Yes })
+                .unwrap();
+        }
+        execute(deps, env, mock_info("devcore1member_a", &[]), ExecuteMsg::Execute { proposal_id })
+    }
+
+    #[test]
+    fn vesting_grant_unlocks_linearly_and_can_be_terminated() {
+        let mut deps = mock_dependencies_with_balance(&coins(1000, "udevcore"));
+        let members = vec![MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) }];
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), InstantiateMsg { members, ..Default::default() }).unwrap();
+
+        let start = mock_env().block.time.seconds() + 100;
+        let stream = ExecuteMsg::from(Propose {
+            schedule: Some(PayoutSchedule::Vesting { start, end: start + 1000, cliff: Some(start + 200) }),
+            ..propose_msg("Maintainer stream", Some("devcore1grantee"), Some(1000))
+        });
+        let res = pass_proposal(deps.as_mut(), mock_env(), stream).unwrap();
+        assert_eq!(0, res.messages.len());
+        assert_eq!(Uint128::from(1000_u128), RESERVED.load(&deps.storage).unwrap());
+
+        let claim = ExecuteMsg::ClaimVested { proposal_id: 0 };
+        let err = execute(deps.as_mut(), env_at(200), mock_info("devcore1grantee", &[]), claim.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+        let err = execute(deps.as_mut(), env_at(600), mock_info("devcore1member_a", &[]), claim.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let res = execute(deps.as_mut(), env_at(600), mock_info("devcore1grantee", &[]), claim.clone()).unwrap();
+        assert_eq!(res.messages[0].msg, grant_payout(GRANT_DENOM, &Addr::unchecked("devcore1grantee"), Uint128::from(500_u128)));
+
+        // The DAO ends the stream at 75% vested: 250 more is paid and 250 goes back to the treasury
+        let terminate = ExecuteMsg::from(Propose {
+            action: Some(ProposalAction::TerminateStream { proposal_id: 0 }),
+            ..propose_msg("Stop stream", None, None)
+        });
+        let res = pass_proposal(deps.as_mut(), env_at(850), terminate).unwrap();
+        assert_eq!(res.messages[0].msg, grant_payout(GRANT_DENOM, &Addr::unchecked("devcore1grantee"), Uint128::from(250_u128)));
+        assert_eq!(Uint128::zero(), RESERVED.load(&deps.storage).unwrap());
+
+        let err = execute(deps.as_mut(), env_at(2000), mock_info("devcore1grantee", &[]), claim).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+
+        // A later clawback has nothing left to reclaim and keeps the stream's end
+        let clawback = ExecuteMsg::from(Propose {
+            action: Some(ProposalAction::Clawback { proposal_id: 0, reason: "Abandoned".to_string() }),
+            ..propose_msg("Claw back stream", None, None)
+        });
+        let res = pass_proposal(deps.as_mut(), env_at(2000), clawback).unwrap();
+        assert_eq!("0", res.attributes.iter().find(|attr| attr.key == "reclaimed").unwrap().value);
+        match load_proposal(&deps.storage, 0).unwrap().payout {
+            Payout::Vesting { terminated_at, .. } => assert_eq!(Some(env_at(850).block.time.seconds()), terminated_at),
+            _ => panic!("expected a vesting grant"),
+        }
+    }
+
+    #[test]
+    fn recurring_grant_pays_each_period_until_stopped() {
+        let mut deps = mock_dependencies_with_balance(&coins(1000, "udevcore"));
+        let members = vec![MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) }];
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), InstantiateMsg { members, ..Default::default() }).unwrap();
+
+        let month = 30 * 24 * 60 * 60;
+        let recurring = |period_amount: Uint128| ExecuteMsg::from(Propose {
+            schedule: Some(PayoutSchedule::Recurring { period_amount, interval: month, periods: 3 }),
+            ..propose_msg("Validator infra", Some("devcore1maintainer"), None)
+        });
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), recurring(Uint128::MAX)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+        pass_proposal(deps.as_mut(), mock_env(), recurring(Uint128::from(100_u128))).unwrap();
+        assert_eq!(Uint128::from(300_u128), RESERVED.load(&deps.storage).unwrap());
+
+        let claim = ExecuteMsg::ClaimRecurring { proposal_id: 0 };
+        let err = execute(deps.as_mut(), env_at(month - 1), mock_info("devcore1maintainer", &[]), claim.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+        let res = execute(deps.as_mut(), env_at(month + 5), mock_info("devcore1maintainer", &[]), claim).unwrap();
+        assert_eq!(res.messages[0].msg, grant_payout(GRANT_DENOM, &Addr::unchecked("devcore1maintainer"), Uint128::from(100_u128)));
+
+        let start = mock_env().block.time.seconds();
+        let res = query(deps.as_ref(), env_at(month + 5), QueryMsg::RecurringSchedule { proposal_id: 0 }).unwrap();
+        let schedule: RecurringResponse = from_binary(&res).unwrap();
+        assert_eq!(Some(start + 2 * month), schedule.next_due);
+        assert_eq!(2, schedule.periods_remaining);
+        assert_eq!(0, schedule.periods_due);
+
+        // Stopping after the second period pays it and returns the third to the treasury
+        let stop = ExecuteMsg::from(Propose {
+            action: Some(ProposalAction::StopRecurring { proposal_id: 0 }),
+            ..propose_msg("Stop infra grant", None, None)
+        });
+        let res = pass_proposal(deps.as_mut(), env_at(2 * month + 5), stop).unwrap();
+        assert_eq!(res.messages[0].msg, grant_payout(GRANT_DENOM, &Addr::unchecked("devcore1maintainer"), Uint128::from(100_u128)));
+        assert_eq!(Uint128::zero(), RESERVED.load(&deps.storage).unwrap());
+
+        let res = query(deps.as_ref(), env_at(4 * month), QueryMsg::RecurringSchedule { proposal_id: 0 }).unwrap();
+        let schedule: RecurringResponse = from_binary(&res).unwrap();
+        assert_eq!(None, schedule.next_due);
+        assert_eq!(0, schedule.periods_remaining);
+    }
+
+    fn apply(deps: DepsMut<CoreumQueries>, applicant: &str, fee: u128) -> Result<Response<CoreumMsg>, ContractError> {
+        let msg = ExecuteMsg::Apply {
+            title: "Coreum SDK for Go".to_string(),
+            description: "Client library".to_string(),
+            requested: Uint128::from(250_u128),
+            recipient: None,
+        };
+        let funds = if fee == 0 { vec![] } else { coins(fee, "udevcore") };
+        execute(deps, mock_env(), mock_info(applicant, &funds), msg)
+    }
+
+    #[test]
+    fn sponsored_application_becomes_proposal() {
+        // The mock does not credit sent funds, the treasury holds the deposited fee
+        let mut deps = mock_dependencies_with_balance(&coins(50, "udevcore"));
+        let members = vec![MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) }];
+        let msg = InstantiateMsg { members, application_fee: Some(Uint128::from(50_u128)), ..Default::default() };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let err = apply(deps.as_mut(), "devcore1outsider", 10).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+        apply(deps.as_mut(), "devcore1outsider", 50).unwrap();
+        assert_eq!(Uint128::from(50_u128), RESERVED.load(&deps.storage).unwrap());
+
+        let sponsor = ExecuteMsg::Sponsor { application_id: 0 };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1outsider", &[]), sponsor.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NotMember { address } if address == "devcore1outsider"));
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), sponsor.clone()).unwrap();
+        assert_eq!(res.messages[0].msg, grant_payout(GRANT_DENOM, &Addr::unchecked("devcore1outsider"), Uint128::from(50_u128)));
+        assert_eq!(Uint128::zero(), RESERVED.load(&deps.storage).unwrap());
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetProposal { proposal_id: 0 }).unwrap();
+        let proposal: Proposal = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("devcore1outsider"), proposal.recipient);
+        assert_eq!(Uint128::from(250_u128), proposal.amount);
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), sponsor).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+    }
+
+    #[test]
+    fn unsponsored_application_expires() {
+        let mut deps = mock_dependencies();
+        let members = vec![MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) }];
+        let msg = InstantiateMsg {
+            members,
+            application_fee: Some(Uint128::from(50_u128)),
+            application_period: Some(1000),
+            burn_expired_fees: Some(true),
+            ..Default::default()
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        apply(deps.as_mut(), "devcore1outsider", 50).unwrap();
+
+        let expire = ExecuteMsg::ExpireApplication { application_id: 0 };
+        let err = execute(deps.as_mut(), env_at(999), mock_info("anyone", &[]), expire.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+        let err = execute(deps.as_mut(), env_at(1000), mock_info("devcore1member_a", &[]), ExecuteMsg::Sponsor { application_id: 0 })
+            .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+
+        let res = execute(deps.as_mut(), env_at(1000), mock_info("anyone", &[]), expire).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Burn { amount: coins(50, "udevcore") })
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetApplication { application_id: 0 }).unwrap();
+        let application: GrantApplication = from_binary(&res).unwrap();
+        assert_eq!(ApplicationStatus::Expired, application.status);
+    }
+
+    #[test]
+    fn voting_opens_after_enough_reviews() {
+        let mut deps = mock_dependencies();
+        let members = vec![
+            MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) },
+            MemberInit { address: "devcore1member_b".to_string(), weight: Uint128::from(20_u128) },
+        ];
+        let msg = InstantiateMsg {
+            members,
+            reviewers: Some(vec![
+                "devcore1reviewer_a".to_string(),
+                "devcore1reviewer_b".to_string(),
+                "devcore1reviewer_c".to_string(),
+            ]),
+            min_reviews: Some(2),
+            ..Default::default()
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let propose = ExecuteMsg::from(propose_msg("Audit", Some("devcore1auditor"), Some(100)));
+        execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), propose).unwrap();
+
+        let vote = ExecuteMsg::Vote { proposal_id: 0, vote: Vote::Yes };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), vote.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::InReview {}));
+
+        let assign = |reviewers: Vec<&str>| ExecuteMsg::AssignReviewers {
+            proposal_id: 0,
+            reviewers: reviewers.into_iter().map(String::from).collect(),
+        };
+        // The proposer cannot pick their own reviewers
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), assign(vec!["devcore1reviewer_a"]))
+            .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1member_b", &[]), assign(vec!["devcore1friend"])).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+        execute(deps.as_mut(), mock_env(), mock_info("devcore1member_b", &[]), assign(vec!["devcore1reviewer_a", "devcore1reviewer_b"])).unwrap();
+
+        let review = |score: u8| ExecuteMsg::SubmitReview {
+            proposal_id: 0,
+            score,
+            comment_hash: "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08".to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1reviewer_c", &[]), review(5)).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), mock_info(MOCK_CONTRACT_ADDR, &[]), assign(vec!["devcore1reviewer_c"])).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1reviewer_a", &[]), review(11)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+        execute(deps.as_mut(), mock_env(), mock_info("devcore1reviewer_a", &[]), review(7)).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1reviewer_a", &[]), review(8)).unwrap_err();
+        assert!(matches!(err, ContractError::AlreadyVoted {}));
+        execute(deps.as_mut(), env_at(60), mock_info("devcore1reviewer_b", &[]), review(9)).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetProposal { proposal_id: 0 }).unwrap();
+        let proposal: Proposal = from_binary(&res).unwrap();
+        assert_eq!(ProposalStatus::Open, proposal.status);
+        assert_eq!((2, 16), (proposal.review_count, proposal.review_score));
+        assert_eq!(env_at(60).block.time.seconds() + VOTING_PERIOD, proposal.voting_end);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ListReviews { proposal_id: 0 }).unwrap();
+        let reviews: Vec<Review> = from_binary(&res).unwrap();
+        assert_eq!(2, reviews.len());
+
+        // Once voting opens the review is over
+        let err = execute(deps.as_mut(), env_at(60), mock_info("devcore1reviewer_c", &[]), review(1)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+        let err = execute(deps.as_mut(), env_at(60), mock_info(MOCK_CONTRACT_ADDR, &[]), assign(vec!["devcore1reviewer_c"]))
+            .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+
+        // A proposal nobody reviews in time is dead and can be closed
+        let propose = ExecuteMsg::from(propose_msg("Second audit", Some("devcore1auditor"), Some(100)));
+        execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), propose).unwrap();
+        let assign = ExecuteMsg::AssignReviewers { proposal_id: 1, reviewers: vec!["devcore1reviewer_a".to_string()] };
+        execute(deps.as_mut(), mock_env(), mock_info("devcore1member_b", &[]), assign).unwrap();
+        let status = |deps: Deps<CoreumQueries>, env: Env| {
+            let res = query(deps, env, QueryMsg::Proposal { proposal_id: 1 }).unwrap();
+            from_binary::<ProposalResponse<CoreumMsg>>(&res).unwrap().status
+        };
+        let close = ExecuteMsg::Close { proposal_id: 1 };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), close.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::VotingOpen {}));
+        assert_eq!(Status::Pending, status(deps.as_ref(), mock_env()));
+        assert_eq!(Status::Rejected, status(deps.as_ref(), env_at(REVIEW_PERIOD)));
+
+        let late_review = ExecuteMsg::SubmitReview { proposal_id: 1, score: 9, comment_hash: "ab".repeat(32) };
+        let err = execute(deps.as_mut(), env_at(REVIEW_PERIOD), mock_info("devcore1reviewer_a", &[]), late_review).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+        execute(deps.as_mut(), env_at(REVIEW_PERIOD), mock_info("anyone", &[]), close).unwrap();
+        assert_eq!(ProposalStatus::Rejected, load_proposal(&deps.storage, 1).unwrap().status);
+
+        execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), vote).unwrap();
+    }
+
+    #[test]
+    fn reviewer_role_is_managed_by_proposal() {
         let mut deps = mock_dependencies();
+        let members = vec![MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) }];
+        let msg = InstantiateMsg { members, reviewers: Some(vec!["devcore1reviewer_a".to_string()]), ..Default::default() };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let update = ExecuteMsg::from(Propose {
+            action: Some(ProposalAction::UpdateReviewers {
+                add: vec!["devcore1reviewer_b".to_string()],
+                remove: vec!["devcore1reviewer_a".to_string()],
+            }),
+            ..propose_msg("Rotate reviewers", None, None)
+        });
+        pass_proposal(deps.as_mut(), mock_env(), update).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ListReviewers {}).unwrap();
+        let reviewers: Vec<Addr> = from_binary(&res).unwrap();
+        assert_eq!(vec![Addr::unchecked("devcore1reviewer_b")], reviewers);
+    }
+
+    #[test]
+    fn clawback_reclaims_escrow_and_bars_grantee() {
+        let mut deps = mock_dependencies_with_balance(&coins(1000, "udevcore"));
+        setup_milestone_grant(deps.as_mut(), Some("devcore1reviewer"));
+
+        let submit = ExecuteMsg::SubmitMilestone { proposal_id: 0, index: 0, deliverable: "ipfs://indexer".to_string() };
+        milestone_msg(deps.as_mut(), "devcore1grantee", submit).unwrap();
+        milestone_msg(deps.as_mut(), "devcore1reviewer", ExecuteMsg::ApproveMilestone { proposal_id: 0, index: 0 }).unwrap();
+        milestone_msg(deps.as_mut(), "anyone", ExecuteMsg::ReleaseMilestone { proposal_id: 0, index: 0 }).unwrap();
+
+        let clawback = ExecuteMsg::from(Propose {
+            action: Some(ProposalAction::Clawback { proposal_id: 0, reason: "Missed deadline".to_string() }),
+            ..propose_msg("Claw back explorer grant", None, None)
+        });
+        let res = pass_proposal(deps.as_mut(), mock_env(), clawback).unwrap();
+        assert_eq!(0, res.messages.len());
+        assert_eq!(Uint128::zero(), RESERVED.load(&deps.storage).unwrap());
+
+        let submit = ExecuteMsg::SubmitMilestone { proposal_id: 0, index: 1, deliverable: "ipfs://frontend".to_string() };
+        let err = milestone_msg(deps.as_mut(), "devcore1grantee", submit).unwrap_err();
+        assert!(matches!(err, ContractError::ClawedBack {}));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetDelinquency { address: "devcore1grantee".to_string() })
+            .unwrap();
+        let delinquency: Option<Delinquency> = from_binary(&res).unwrap();
+        assert_eq!(Some(0), delinquency.map(|d| d.proposal_id));
+
+        let propose = ExecuteMsg::from(propose_msg("Explorer v2", Some("devcore1grantee"), Some(100)));
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), propose).unwrap_err();
+        assert!(matches!(err, ContractError::DelinquentRecipient {}));
+    }
+
+    #[test]
+    fn recipients_are_checked_against_compliance_lists() {
+        let mut deps = mock_dependencies_with_balance(&coins(1000, "udevcore"));
+        let members = vec![MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) }];
+        let msg = InstantiateMsg {
+            members,
+            allowlist_enabled: Some(true),
+            compliance: Some("devcore1compliance".to_string()),
+            ..Default::default()
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
 
+        let propose = ExecuteMsg::from(propose_msg("Wallet integration", Some("devcore1team_a"), Some(100)));
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), propose.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::RecipientNotAllowed {}));
+
+        let allow = ExecuteMsg::UpdateRecipientLists {
+            update: RecipientListUpdate { allow: vec!["devcore1team_a".to_string()], ..Default::default() },
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), allow.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), mock_info("devcore1compliance", &[]), allow).unwrap();
+
+        execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), propose).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), ExecuteMsg::Vote { proposal_id: 0, vote: Vote::Yes })
+            .unwrap();
+
+        // Blocked while the vote was running, so execution is refused
+        let block = ExecuteMsg::UpdateRecipientLists {
+            update: RecipientListUpdate { block: vec!["devcore1team_a".to_string()], ..Default::default() },
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("devcore1compliance", &[]), block).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), ExecuteMsg::Execute { proposal_id: 0 })
+            .unwrap_err();
+        assert!(matches!(err, ContractError::RecipientBlocked {}));
+
+        let disable = ExecuteMsg::from(Propose {
+            action: Some(ProposalAction::SetAllowlistMode { enabled: false }),
+            ..propose_msg("Disable allow-list", None, None)
+        });
+        pass_proposal(deps.as_mut(), mock_env(), disable).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let config: Config = from_binary(&res).unwrap();
+        assert!(!config.allowlist_enabled);
+    }
+
+    fn round_application(title: &str, recipient: &str, amount: u128) -> RoundApplicationInit {
+        RoundApplicationInit {
+            title: title.to_string(),
+            description: format!("{} description", title),
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+        }
+    }
+
+    fn setup_round(deps: DepsMut<CoreumQueries>, method: TallyMethod, budget: u128) {
         let members = vec![
-            Member {
-                address: Addr::unchecked("addr1"),
-                weight: Uint128::from(10_u128),
-            },
+            MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) },
+            MemberInit { address: "devcore1member_b".to_string(), weight: Uint128::from(20_u128) },
+            MemberInit { address: "devcore1member_c".to_string(), weight: Uint128::from(15_u128) },
         ];
-        let msg = InstantiateMsg { members };
-        let info = mock_info("creator", &[]);
-        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let mut deps = deps;
+        instantiate(deps.branch(), mock_env(), mock_info("creator", &[]), InstantiateMsg { members, ..Default::default() }).unwrap();
 
-        // Propose
-        let info = mock_info("addr1", &[]);
-        let proposal_msg = ExecuteMsg::Propose {
-            title: "Some Title".to_string(),
-            description: "Some Description".to_string(),
-            amount: Some(Uint128::from(100_u128)),
-            recipient: Some(Addr::unchecked("recipient_address")),
+        let action = ProposalAction::CreateRound {
+            title: "Round 1".to_string(),
+            description: "Tooling grants".to_string(),
+            method,
+            budget: Uint128::from(budget),
+            applications: vec![
+                round_application("Explorer", "devcore1team_a", 400),
+                round_application("Wallet", "devcore1team_b", 500),
+                round_application("Indexer", "devcore1team_c", 300),
+            ],
         };
-        execute(deps.as_mut(), mock_env(), info.clone(), proposal_msg).unwrap();
+        let msg = ExecuteMsg::from(Propose {
+            action: Some(action),
+            ..propose_msg("Open round 1", None, None)
+        });
+        pass_proposal(deps, mock_env(), msg).unwrap();
+    }
 
-        let vote_msg = ExecuteMsg::Vote {
-            proposal_id: 0,
-            approve: true,
+    fn vote_round(deps: DepsMut<CoreumQueries>, voter: &str, choices: Vec<u32>) -> Result<Response<CoreumMsg>, ContractError> {
+        let msg = ExecuteMsg::VoteRound { round_id: 0, choices };
+        execute(deps, mock_env(), mock_info(voter, &[]), msg)
+    }
+
+    fn finalize_round(deps: DepsMut<CoreumQueries>) -> Result<Response<CoreumMsg>, ContractError> {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(VOTING_PERIOD);
+        execute(deps, env, mock_info("anyone", &[]), ExecuteMsg::FinalizeRound { round_id: 0 })
+    }
+
+    #[test]
+    fn approval_round_pays_winners_within_budget() {
+        let mut deps = mock_dependencies_with_balance(&coins(1000, "udevcore"));
+        setup_round(deps.as_mut(), TallyMethod::Approval, 800);
+        assert_eq!(Uint128::from(800_u128), RESERVED.load(&deps.storage).unwrap());
+
+        vote_round(deps.as_mut(), "devcore1member_a", vec![0, 2]).unwrap();
+        vote_round(deps.as_mut(), "devcore1member_b", vec![1]).unwrap();
+        vote_round(deps.as_mut(), "devcore1member_c", vec![0, 1]).unwrap();
+
+        // Wallet (35) wins first; Explorer (25) no longer fits, Indexer (10) does
+        let res = finalize_round(deps.as_mut()).unwrap();
+        assert_eq!(2, res.messages.len());
+        assert_eq!(Uint128::zero(), RESERVED.load(&deps.storage).unwrap());
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetRound { round_id: 0 }).unwrap();
+        let round: GrantRound = from_binary(&res).unwrap();
+        assert!(round.finalized);
+        assert_eq!(vec![1, 2], round.winners);
+    }
+
+    #[test]
+    fn ranked_choice_round_transfers_eliminated_votes() {
+        let mut deps = mock_dependencies_with_balance(&coins(1000, "udevcore"));
+        setup_round(deps.as_mut(), TallyMethod::RankedChoice, 500);
+
+        vote_round(deps.as_mut(), "devcore1member_a", vec![2, 0]).unwrap();
+        vote_round(deps.as_mut(), "devcore1member_b", vec![1, 0]).unwrap();
+        vote_round(deps.as_mut(), "devcore1member_c", vec![0, 2]).unwrap();
+
+        // Winners are checked again when they are paid
+        RECIPIENT_BLOCKLIST.save(&mut deps.storage, "devcore1team_a", &Addr::unchecked("devcore1team_a")).unwrap();
+        let err = finalize_round(deps.as_mut()).unwrap_err();
+        assert!(matches!(err, ContractError::RecipientBlocked {}));
+
+        // Nor can a blocked recipient enter a new round
+        let action = ProposalAction::CreateRound {
+            title: "Round 2".to_string(),
+            description: "More tooling grants".to_string(),
+            method: TallyMethod::RankedChoice,
+            budget: Uint128::from(500_u128),
+            applications: vec![
+                round_application("Explorer", "devcore1team_a", 400),
+                round_application("Wallet", "devcore1team_b", 500),
+            ],
         };
+        let msg = ExecuteMsg::from(Propose { action: Some(action), ..propose_msg("Open round 2", None, None) });
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::RecipientBlocked {}));
+        RECIPIENT_BLOCKLIST.remove(&mut deps.storage, "devcore1team_a");
 
-        let res = execute(deps.as_mut(), mock_env(), info, vote_msg).unwrap();
-        assert_eq!(0, res.messages.len());
+        // Indexer is eliminated first and its weight moves to Explorer, which then beats Wallet 25 to 20
+        let res = finalize_round(deps.as_mut()).unwrap();
+        assert_eq!(1, res.messages.len());
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetRound { round_id: 0 }).unwrap();
+        let round: GrantRound = from_binary(&res).unwrap();
+        assert_eq!(vec![0], round.winners);
+    }
+
+    fn setup_quadratic_round(deps: DepsMut<CoreumQueries>, matching_pool: u128, contribution_cap: Option<u128>) {
+        let members = vec![MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) }];
+        let mut deps = deps;
+        instantiate(deps.branch(), mock_env(), mock_info("creator", &[]), InstantiateMsg { members, ..Default::default() }).unwrap();
+
+        let action = ProposalAction::CreateQuadraticRound {
+            title: "QF Round".to_string(),
+            description: "Public goods".to_string(),
+            matching_pool: Uint128::from(matching_pool),
+            contribution_cap: contribution_cap.map(Uint128::from),
+            applications: vec![
+                FundingApplicationInit {
+                    title: "Docs".to_string(),
+                    description: "Developer docs".to_string(),
+                    recipient: "devcore1team_a".to_string(),
+                },
+                FundingApplicationInit {
+                    title: "Faucet".to_string(),
+                    description: "Testnet faucet".to_string(),
+                    recipient: "devcore1team_b".to_string(),
+                },
+            ],
+        };
+        let msg = ExecuteMsg::from(Propose {
+            action: Some(action),
+            ..propose_msg("Open a QF round", None, None)
+        });
+        pass_proposal(deps, mock_env(), msg).unwrap();
+    }
+
+    fn contribute(deps: DepsMut<CoreumQueries>, contributor: &str, application: u32, amount: u128) -> Result<Response<CoreumMsg>, ContractError> {
+        let msg = ExecuteMsg::Contribute { round_id: 0, application };
+        execute(deps, mock_env(), mock_info(contributor, &coins(amount, "udevcore")), msg)
     }
 
+    fn quadratic_matching_of(deps: Deps<CoreumQueries>) -> Vec<Uint128> {
+        let res = query(deps, mock_env(), QueryMsg::QuadraticMatching { round_id: 0 }).unwrap();
+        let matching: Vec<MatchingResponse> = from_binary(&res).unwrap();
+        matching.into_iter().map(|m| m.matched).collect()
+    }
 
     #[test]
-    fn execute_proposal() {
+    fn quadratic_round_matches_broad_support() {
+        let mut deps = mock_dependencies_with_balance(&coins(2000, "udevcore"));
+        setup_quadratic_round(deps.as_mut(), 1000, None);
+
+        // Same total raised, but Docs has two backers and Faucet only one
+        contribute(deps.as_mut(), "alice", 0, 100).unwrap();
+        contribute(deps.as_mut(), "bob", 0, 100).unwrap();
+        contribute(deps.as_mut(), "carol", 1, 200).unwrap();
+        assert_eq!(vec![Uint128::from(200_u128), Uint128::zero()], quadratic_matching_of(deps.as_ref()));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ListContributions { round_id: 0, application: 0 }).unwrap();
+        let contributions: Vec<Contribution> = from_binary(&res).unwrap();
+        assert_eq!(2, contributions.len());
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(VOTING_PERIOD);
+        let msg = ExecuteMsg::FinalizeQuadraticRound { round_id: 0 };
+        RECIPIENT_BLOCKLIST.save(&mut deps.storage, "devcore1team_b", &Addr::unchecked("devcore1team_b")).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::RecipientBlocked {}));
+        RECIPIENT_BLOCKLIST.remove(&mut deps.storage, "devcore1team_b");
+        let res = execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap();
+        assert_eq!(2, res.messages.len());
+        assert_eq!(res.messages[0].msg, grant_payout(GRANT_DENOM, &Addr::unchecked("devcore1team_a"), Uint128::from(400_u128)));
+        assert_eq!(Uint128::zero(), RESERVED.load(&deps.storage).unwrap());
+    }
+
+    #[test]
+    fn quadratic_matching_respects_cap_and_pool() {
+        let mut deps = mock_dependencies_with_balance(&coins(2000, "udevcore"));
+        setup_quadratic_round(deps.as_mut(), 150, Some(100));
+
+        // The whale's 900 only counts as 100 towards matching
+        contribute(deps.as_mut(), "alice", 0, 100).unwrap();
+        contribute(deps.as_mut(), "whale", 0, 900).unwrap();
+        contribute(deps.as_mut(), "bob", 1, 100).unwrap();
+        contribute(deps.as_mut(), "carol", 1, 100).unwrap();
+        contribute(deps.as_mut(), "dave", 1, 100).unwrap();
+
+        // Ideal matches are 200 and 600, scaled down to fit the pool of 150
+        assert_eq!(vec![Uint128::from(37_u128), Uint128::from(112_u128)], quadratic_matching_of(deps.as_ref()));
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("eve", &coins(10, "uother")),
+            ExecuteMsg::Contribute { round_id: 0, application: 0 }).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+    }
+
+    #[test]
+    fn round_ballots_are_validated() {
+        let mut deps = mock_dependencies_with_balance(&coins(1000, "udevcore"));
+        setup_round(deps.as_mut(), TallyMethod::RankedChoice, 500);
+
+        let err = vote_round(deps.as_mut(), "devcore1outsider", vec![0]).unwrap_err();
+        assert!(matches!(err, ContractError::NotMember { address } if address == "devcore1outsider"));
+        // Ballots are weighed with the members at the round's creation
+        let member_d = Member { address: Addr::unchecked("devcore1member_d"), weight: Uint128::from(100_u128) };
+        MEMBERS.save(&mut deps.storage, "devcore1member_d", &member_d, mock_env().block.height).unwrap();
+        let err = vote_round(deps.as_mut(), "devcore1member_d", vec![0]).unwrap_err();
+        assert!(matches!(err, ContractError::NotMember { .. }));
+        let err = vote_round(deps.as_mut(), "devcore1member_a", vec![0, 0]).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+        let err = vote_round(deps.as_mut(), "devcore1member_a", vec![3]).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+
+        vote_round(deps.as_mut(), "devcore1member_a", vec![0]).unwrap();
+        let err = vote_round(deps.as_mut(), "devcore1member_a", vec![1]).unwrap_err();
+        assert!(matches!(err, ContractError::AlreadyVoted {}));
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), ExecuteMsg::FinalizeRound { round_id: 0 })
+            .unwrap_err();
+        assert!(matches!(err, ContractError::VotingOpen {}));
+    }
+
+    #[test]
+    fn guardian_pauses_payouts_and_can_be_replaced() {
+        let mut deps = mock_dependencies_with_balance(&coins(1000, "udevcore"));
+        let members = vec![MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) }];
+        let msg = InstantiateMsg { members, guardian: Some("devcore1guardian".to_string()), ..Default::default() };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let grant = ExecuteMsg::from(propose_msg("Audit", Some("devcore1grantee"), Some(100)));
+        let member = mock_info("devcore1member_a", &[]);
+        execute(deps.as_mut(), mock_env(), member.clone(), grant).unwrap();
+
+        let until = mock_env().block.time.seconds() + 3600;
+        let err = execute(deps.as_mut(), mock_env(), member.clone(), ExecuteMsg::Pause { until }).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), mock_info("devcore1guardian", &[]), ExecuteMsg::Pause { until }).unwrap();
+
+        // Votes still count while paused, payouts wait
+        execute(deps.as_mut(), mock_env(), member.clone(), ExecuteMsg::Vote { proposal_id: 0, vote: Vote::Yes }).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), member.clone(), ExecuteMsg::Execute { proposal_id: 0 }).unwrap_err();
+        assert!(matches!(err, ContractError::Paused { until: u } if u == until));
+        let res = execute(deps.as_mut(), env_at(3600), member, ExecuteMsg::Execute { proposal_id: 0 }).unwrap();
+        assert_eq!(1, res.messages.len());
+
+        // The DAO removes the guardian in the middle of a pause, which lifts it
+        execute(deps.as_mut(), mock_env(), mock_info("devcore1guardian", &[]), ExecuteMsg::Pause { until: until + 3600 })
+            .unwrap();
+        let remove = ExecuteMsg::from(Propose {
+            action: Some(ProposalAction::SetGuardian { address: None }),
+            ..propose_msg("Remove guardian", None, None)
+        });
+        pass_proposal(deps.as_mut(), env_at(3600), remove).unwrap();
+        let config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!((None, None), (config.guardian, config.paused_until));
+    }
+
+    #[test]
+    fn veto_council_stops_passed_proposals_during_timelock() {
+        let mut deps = mock_dependencies_with_balance(&coins(1000, "udevcore"));
+        let members = vec![MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) }];
+        let msg = InstantiateMsg {
+            members,
+            timelock: Some(3600),
+            veto_council: Some(VetoCouncilInit::Addresses(vec!["devcore1council".to_string()])),
+            ..Default::default()
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let propose = |title: &str, action: Option<ProposalAction>| ExecuteMsg::from(Propose {
+            amount: action.is_none().then(|| Uint128::from(100_u128)),
+            action,
+            ..propose_msg(title, Some("devcore1grantee"), None)
+        });
+        let member = mock_info("devcore1member_a", &[]);
+        let council = mock_info("devcore1council", &[]);
+        for title in ["Vetoed grant", "Timelocked grant"] {
+            execute(deps.as_mut(), mock_env(), member.clone(), propose(title, None)).unwrap();
+        }
+        for proposal_id in 0..2 {
+            execute(deps.as_mut(), mock_env(), member.clone(), ExecuteMsg::Vote { proposal_id, vote: Vote::Yes }).unwrap();
+        }
+
+        let veto = |proposal_id| ExecuteMsg::Veto { proposal_id, reason: "Recipient is a sanctioned entity".to_string() };
+        let err = execute(deps.as_mut(), mock_env(), council.clone(), veto(0)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+        let err = execute(deps.as_mut(), env_at(VOTING_PERIOD), member.clone(), veto(0)).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), env_at(VOTING_PERIOD), council.clone(), veto(0)).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetProposal { proposal_id: 0 }).unwrap();
+        let proposal: Proposal = from_binary(&res).unwrap();
+        let reason = "Recipient is a sanctioned entity".to_string();
+        assert_eq!(ProposalStatus::Vetoed { by: Addr::unchecked("devcore1council"), reason }, proposal.status);
+        let after_timelock = env_at(VOTING_PERIOD + 3600);
+        let err = execute(deps.as_mut(), after_timelock.clone(), member.clone(), ExecuteMsg::Execute { proposal_id: 0 })
+            .unwrap_err();
+        assert!(matches!(err, ContractError::Vetoed {}));
+        // A veto is final: cw3 lists the proposal as rejected and it cannot be closed again
+        let res = query(deps.as_ref(), after_timelock.clone(), QueryMsg::Proposal { proposal_id: 0 }).unwrap();
+        assert_eq!(Status::Rejected, from_binary::<ProposalResponse<CoreumMsg>>(&res).unwrap().status);
+        let err = execute(deps.as_mut(), after_timelock.clone(), member.clone(), ExecuteMsg::Close { proposal_id: 0 })
+            .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+
+        let err = execute(deps.as_mut(), env_at(VOTING_PERIOD), member.clone(), ExecuteMsg::Execute { proposal_id: 1 })
+            .unwrap_err();
+        assert!(matches!(err, ContractError::Timelocked { .. }));
+        let res = execute(deps.as_mut(), after_timelock.clone(), member.clone(), ExecuteMsg::Execute { proposal_id: 1 })
+            .unwrap();
+        assert_eq!(1, res.messages.len());
+
+        // The DAO takes the veto power away
+        let disband = propose("Disband council", Some(ProposalAction::SetVetoCouncil { council: None }));
+        execute(deps.as_mut(), mock_env(), member.clone(), disband).unwrap();
+        execute(deps.as_mut(), mock_env(), member.clone(), ExecuteMsg::Vote { proposal_id: 2, vote: Vote::Yes }).unwrap();
+        execute(deps.as_mut(), after_timelock, member, ExecuteMsg::Execute { proposal_id: 2 }).unwrap();
+        assert_eq!(None, CONFIG.load(&deps.storage).unwrap().veto_council);
+    }
+
+    #[test]
+    fn proposal_lifecycle_emits_events() {
+        let mut deps = mock_dependencies_with_balance(&coins(1000, "udevcore"));
+        let members = vec![MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) }];
+        let res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), InstantiateMsg { members, ..Default::default() })
+            .unwrap();
+        assert_eq!(events::MEMBER_CHANGED, res.events[0].ty);
+
+        let attribute = |event: &cosmwasm_std::Event, key: &str| {
+            event.attributes.iter().find(|attr| attr.key == key).map(|attr| attr.value.clone()).unwrap()
+        };
+        let info = mock_info("devcore1member_a", &[]);
+        let msg = ExecuteMsg::from(propose_msg("Indexer", Some("devcore1grantee"), Some(100)));
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let created = &res.events[0];
+        assert_eq!(events::PROPOSAL_CREATED, created.ty);
+        assert_eq!("devcore1member_a", attribute(created, events::ATTR_PROPOSER));
+        assert_eq!("100", attribute(created, events::ATTR_AMOUNT));
+        assert_eq!("open", attribute(created, events::ATTR_STATUS));
+
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::Vote { proposal_id: 0, vote: Vote::Yes }).unwrap();
+        let vote = &res.events[0];
+        assert_eq!(events::VOTE_CAST, vote.ty);
+        assert_eq!(
+            vec!["0", "devcore1member_a", "yes", "10", "10", "0", "0"],
+            vote.attributes.iter().map(|attr| attr.value.as_str()).collect::<Vec<&str>>()
+        );
+
+        let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Execute { proposal_id: 0 }).unwrap();
+        let types = res.events.iter().map(|event| event.ty.as_str()).collect::<Vec<&str>>();
+        assert_eq!(vec![events::PROPOSAL_EXECUTED, events::GRANT_PAID], types);
+        assert_eq!("devcore1grantee", attribute(&res.events[1], events::ATTR_RECIPIENT));
+    }
+
+    #[test]
+    fn milestone_and_list_changes_emit_events() {
+        let mut deps = mock_dependencies_with_balance(&coins(1000, "udevcore"));
+        setup_milestone_grant(deps.as_mut(), Some("devcore1reviewer"));
+
+        let submit = ExecuteMsg::SubmitMilestone { proposal_id: 0, index: 0, deliverable: "https://example.com/v1".to_string() };
+        let res = milestone_msg(deps.as_mut(), "devcore1grantee", submit).unwrap();
+        assert_eq!(events::MILESTONE_CHANGED, res.events[0].ty);
+        assert_eq!(
+            vec!["0", "0", "submitted", "0"],
+            res.events[0].attributes.iter().map(|attr| attr.value.as_str()).collect::<Vec<&str>>()
+        );
+        let res = milestone_msg(deps.as_mut(), "devcore1reviewer", ExecuteMsg::CancelMilestone { proposal_id: 0, index: 1 })
+            .unwrap();
+        assert_eq!(
+            vec!["0", "1", "cancelled", "0"],
+            res.events[0].attributes.iter().map(|attr| attr.value.as_str()).collect::<Vec<&str>>()
+        );
+
+        let update = RecipientListUpdate { block: vec!["devcore1spammer".to_string()], ..Default::default() };
+        let block = ExecuteMsg::from(Propose {
+            action: Some(ProposalAction::UpdateRecipientLists { update }),
+            ..propose_msg("Block spammer", None, None)
+        });
+        let res = pass_proposal(deps.as_mut(), mock_env(), block).unwrap();
+        let changed = res.events.iter().find(|event| event.ty == events::RECIPIENT_LIST_CHANGED).unwrap();
+        assert_eq!(
+            vec!["blocklist", "devcore1spammer", "true"],
+            changed.attributes.iter().map(|attr| attr.value.as_str()).collect::<Vec<&str>>()
+        );
+    }
+
+    #[test]
+    fn cw3_interface() {
+        let mut deps = mock_dependencies_with_balance(&coins(1000, "udevcore"));
+        let members = vec![
+            MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) },
+            MemberInit { address: "devcore1member_b".to_string(), weight: Uint128::from(20_u128) },
+            MemberInit { address: "devcore1member_c".to_string(), weight: Uint128::from(30_u128) },
+        ];
+        let msg = InstantiateMsg { members, quorum: Some(Decimal::percent(50)), ..Default::default() };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // A plain cw3 proposal: no grant, only messages, voting ends in an hour
+        let payment: CosmosMsg<CoreumMsg> = BankMsg::Send { to_address: "devcore1vendor".to_string(), amount: coins(50, "udevcore") }.into();
+        let latest = Expiration::AtTime(Timestamp::from_seconds(mock_env().block.time.seconds() + 3600));
+        let propose = |title: &str| ExecuteMsg::from(Propose {
+            msgs: vec![payment.clone()],
+            latest: Some(latest),
+            ..propose_msg(title, None, None)
+        });
+        for title in ["Hosting", "Hosting again", "Hosting once more"] {
+            execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), propose(title)).unwrap();
+        }
+
+        let vote = |deps: DepsMut<CoreumQueries>, voter: &str, proposal_id: u64, vote: Vote| {
+            execute(deps, mock_env(), mock_info(voter, &[]), ExecuteMsg::Vote { proposal_id, vote })
+        };
+        vote(deps.as_mut(), "devcore1member_a", 0, Vote::Yes).unwrap();
+        vote(deps.as_mut(), "devcore1member_b", 0, Vote::Abstain).unwrap();
+        vote(deps.as_mut(), "devcore1member_c", 1, Vote::Veto).unwrap();
+        vote(deps.as_mut(), "devcore1member_a", 2, Vote::Yes).unwrap();
+
+        // member_c's missing ballot could still turn the first proposal, so it stays open until voting ends
+        let status = |deps: Deps<CoreumQueries>, env: Env, proposal_id: u64| {
+            let res = query(deps, env, QueryMsg::Proposal { proposal_id }).unwrap();
+            from_binary::<ProposalResponse<CoreumMsg>>(&res).unwrap()
+        };
+        assert_eq!(Status::Open, status(deps.as_ref(), mock_env(), 0).status);
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), ExecuteMsg::Execute { proposal_id: 0 })
+            .unwrap_err();
+        assert!(matches!(err, ContractError::NotPassed {}));
+
+        let proposal = status(deps.as_ref(), env_at(3600), 0);
+        assert_eq!((Status::Passed, latest, vec![payment.clone()]), (proposal.status, proposal.expires, proposal.msgs));
+        assert_eq!(
+            ThresholdResponse::ThresholdQuorum { threshold: Decimal::percent(50), quorum: Decimal::percent(50), total_weight: 60 },
+            proposal.threshold
+        );
+        // A lone yes from a sixth of the weight misses the quorum
+        assert_eq!(Status::Rejected, status(deps.as_ref(), env_at(3600), 2).status);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ListVotes { proposal_id: 0, start_after: None, limit: None })
+            .unwrap();
+        let votes: VoteListResponse = from_binary(&res).unwrap();
+        let votes = votes.votes.into_iter().map(|vote| (vote.voter, vote.vote, vote.weight)).collect::<Vec<_>>();
+        assert_eq!(
+            vec![("devcore1member_a".to_string(), Vote::Yes, 10), ("devcore1member_b".to_string(), Vote::Abstain, 20)],
+            votes
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ListVoters { start_after: Some("devcore1member_a".to_string()), limit: Some(1) })
+            .unwrap();
+        let voters: VoterListResponse = from_binary(&res).unwrap();
+        assert_eq!(vec![VoterDetail { addr: "devcore1member_b".to_string(), weight: 20 }], voters.voters);
+
+        let res = execute(deps.as_mut(), env_at(3600), mock_info("anyone", &[]), ExecuteMsg::Execute { proposal_id: 0 }).unwrap();
+        assert_eq!(vec![payment], res.messages.into_iter().map(|msg| msg.msg).collect::<Vec<CosmosMsg<CoreumMsg>>>());
+
+        // The vetoed proposal can be closed once its voting period is over
+        let close = ExecuteMsg::Close { proposal_id: 1 };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), close.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::VotingOpen {}));
+        execute(deps.as_mut(), env_at(3600), mock_info("anyone", &[]), close).unwrap();
+
+        let res = query(deps.as_ref(), env_at(3600), QueryMsg::ReverseProposals { start_before: None, limit: None }).unwrap();
+        let list: ProposalListResponse = from_binary(&res).unwrap();
+        let statuses = list.proposals.iter().map(|proposal| (proposal.id, proposal.status)).collect::<Vec<_>>();
+        assert_eq!(vec![(2, Status::Rejected), (1, Status::Rejected), (0, Status::Executed)], statuses);
+    }
+
+    #[test]
+    fn proposal_messages_are_checked_like_grants() {
+        let mut deps = mock_dependencies_with_balance(&coins(1000, "udevcore"));
+        let members = vec![MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) }];
+        let msg = InstantiateMsg {
+            members,
+            governance_token: Some(GOVERNANCE_TOKEN.to_string()),
+            staking_mode: Some(StakingMode::Supplement),
+            cw20_tokens: Some(vec!["devcore1cw20token".to_string()]),
+            ..Default::default()
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        RECIPIENT_BLOCKLIST.save(&mut deps.storage, "devcore1spammer", &Addr::unchecked("devcore1spammer")).unwrap();
+
+        let send = |to: &str, amount: u128| -> CosmosMsg<CoreumMsg> {
+            BankMsg::Send { to_address: to.to_string(), amount: coins(amount, "udevcore") }.into()
+        };
+        let propose = |msgs: Vec<CosmosMsg<CoreumMsg>>, action: Option<ProposalAction>| ExecuteMsg::from(Propose {
+            msgs,
+            action,
+            ..propose_msg("Pay", None, None)
+        });
+        let info = mock_info("devcore1member_a", &[]);
+
+        // An action proposal could otherwise smuggle payouts past the pause
+        let set_guardian = Some(ProposalAction::SetGuardian { address: None });
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), propose(vec![send("devcore1vendor", 50)], set_guardian))
+            .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), propose(vec![send("devcore1spammer", 50)], None)).unwrap_err();
+        assert!(matches!(err, ContractError::RecipientBlocked {}));
+
+        // Funds only leave through checked bank sends
+        let transfer = to_binary(&Cw20ExecuteMsg::Transfer { recipient: "devcore1vendor".to_string(), amount: Uint128::from(50_u128) })
+            .unwrap();
+        let execute_msg = |contract: &str, msg: Binary, funds: Vec<Coin>| -> CosmosMsg<CoreumMsg> {
+            WasmMsg::Execute { contract_addr: contract.to_string(), msg, funds }.into()
+        };
+        let unchecked = vec![
+            execute_msg("devcore1vendor", to_binary(&"pay").unwrap(), coins(50, "udevcore")),
+            WasmMsg::Instantiate {
+                admin: None,
+                code_id: 1,
+                msg: to_binary(&"init").unwrap(),
+                funds: coins(50, "udevcore"),
+                label: "vendor".to_string(),
+            }
+            .into(),
+            execute_msg("devcore1cw20token", transfer.clone(), vec![]),
+            execute_msg("devcore1other_cw20", transfer, vec![]),
+            BankMsg::Send { to_address: "devcore1vendor".to_string(), amount: coins(50, GOVERNANCE_TOKEN) }.into(),
+            CoreumMsg::AssetFT(assetft::Msg::Mint { coin: Coin::new(50, GOVERNANCE_TOKEN) }).into(),
+        ];
+        for msg in unchecked {
+            let err = execute(deps.as_mut(), mock_env(), info.clone(), propose(vec![msg], None)).unwrap_err();
+            assert!(matches!(err, ContractError::InvalidInput(_)));
+        }
+        let call = execute_msg("devcore1vendor", to_binary(&"pay").unwrap(), vec![]);
+        execute(deps.as_mut(), mock_env(), info.clone(), propose(vec![call], None)).unwrap();
+
+        // Sends count against the unreserved treasury at execution
+        RESERVED.save(&mut deps.storage, &Uint128::from(960_u128)).unwrap();
+        let err = pass_proposal(deps.as_mut(), mock_env(), propose(vec![send("devcore1vendor", 50)], None)).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InsufficientTreasury { available, required }
+                if available == Uint128::from(40_u128) && required == Uint128::from(50_u128)
+        ));
+    }
+
+    type App = BasicApp<CoreumMsg, CoreumQueries>;
+
+    fn mock_app() -> App {
+        BasicAppBuilder::<CoreumMsg, CoreumQueries>::new_custom().build(|_, _, _| {})
+    }
+
+    fn dao_contract() -> Box<dyn Contract<CoreumMsg, CoreumQueries>> {
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+
+    fn group_contract() -> Box<dyn Contract<CoreumMsg, CoreumQueries>> {
+        Box::new(ContractWrapper::new_with_empty(
+            cw4_group::contract::execute,
+            cw4_group::contract::instantiate,
+            cw4_group::contract::query,
+        ))
+    }
+
+    fn propose_in(app: &mut App, dao: &Addr, proposer: &str) -> anyhow::Result<AppResponse> {
+        let msg = ExecuteMsg::from(propose_msg("Docs", None, Some(100)));
+        app.execute_contract(Addr::unchecked(proposer), dao.clone(), &msg, &[])
+    }
+
+    fn vote_in(app: &mut App, dao: &Addr, voter: &str, vote: Vote) -> anyhow::Result<AppResponse> {
+        let msg = ExecuteMsg::Vote { proposal_id: 0, vote };
+        app.execute_contract(Addr::unchecked(voter), dao.clone(), &msg, &[])
+    }
+
+    fn total_weight_of(app: &App, dao: &Addr) -> u64 {
+        match app.wrap().query_wasm_smart(dao, &QueryMsg::Threshold {}).unwrap() {
+            ThresholdResponse::ThresholdQuorum { total_weight, .. } => total_weight,
+            threshold => panic!("unexpected threshold {:?}", threshold),
+        }
+    }
+
+    #[test]
+    fn built_in_membership_is_the_default() {
+        let mut app = mock_app();
+        let dao_id = app.store_code(dao_contract());
+        let members = vec![
+            MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) },
+            MemberInit { address: "devcore1member_b".to_string(), weight: Uint128::from(20_u128) },
+        ];
+        let msg = InstantiateMsg { members, ..Default::default() };
+        let dao = app.instantiate_contract(dao_id, Addr::unchecked("creator"), &msg, &[], "dao", None).unwrap();
+
+        propose_in(&mut app, &dao, "devcore1member_a").unwrap();
+        vote_in(&mut app, &dao, "devcore1member_b", Vote::Yes).unwrap();
+        let err = vote_in(&mut app, &dao, "devcore1outsider", Vote::Yes).unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(ContractError::NotMember { .. })));
+        assert_eq!(30, total_weight_of(&app, &dao));
+
+        // Without a group nobody may report membership changes
+        let hook = ExecuteMsg::MemberChangedHook(MemberChangedHookMsg::new(vec![]));
+        let err = app.execute_contract(Addr::unchecked("devcore1member_a"), dao, &hook, &[]).unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(ContractError::Unauthorized {})));
+    }
+
+    #[test]
+    fn membership_can_come_from_a_cw4_group() {
+        let mut app = mock_app();
+        let group_id = app.store_code(group_contract());
+        let dao_id = app.store_code(dao_contract());
+        let admin = Addr::unchecked("devcore1admin");
+        let group_members = vec![
+            cw4::Member { addr: "devcore1member_a".to_string(), weight: 10 },
+            cw4::Member { addr: "devcore1member_b".to_string(), weight: 20 },
+        ];
+        let msg = cw4_group::msg::InstantiateMsg { admin: Some(admin.to_string()), members: group_members };
+        let group = app.instantiate_contract(group_id, admin.clone(), &msg, &[], "group", None).unwrap();
+
+        // The member list has to be left to the group
+        let members = vec![MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) }];
+        let msg = InstantiateMsg { members, group: Some(group.to_string()), ..Default::default() };
+        let err = app.instantiate_contract(dao_id, admin.clone(), &msg, &[], "dao", None).unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(ContractError::InvalidInput(_))));
+
+        let msg = InstantiateMsg { group: Some(group.to_string()), ..Default::default() };
+        let dao = app.instantiate_contract(dao_id, admin.clone(), &msg, &[], "dao", None).unwrap();
+        let add_hook = cw4_group::msg::ExecuteMsg::AddHook { addr: dao.to_string() };
+        app.execute_contract(admin.clone(), group.clone(), &add_hook, &[]).unwrap();
+        assert_eq!(30, total_weight_of(&app, &dao));
+
+        app.update_block(next_block);
+        propose_in(&mut app, &dao, "devcore1member_a").unwrap();
+        app.update_block(next_block);
+
+        // A member who joins after the proposal counts towards the total but cannot vote on it
+        let update = cw4_group::msg::ExecuteMsg::UpdateMembers {
+            remove: vec![],
+            add: vec![cw4::Member { addr: "devcore1member_c".to_string(), weight: 30 }],
+        };
+        app.execute_contract(admin, group, &update, &[]).unwrap();
+        assert_eq!(60, total_weight_of(&app, &dao));
+        let voter: VoterResponse = app.wrap()
+            .query_wasm_smart(&dao, &QueryMsg::Voter { address: "devcore1member_c".to_string() })
+            .unwrap();
+        assert_eq!(Some(30), voter.weight);
+
+        let err = vote_in(&mut app, &dao, "devcore1member_c", Vote::No).unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(ContractError::NotMember { .. })));
+        vote_in(&mut app, &dao, "devcore1member_b", Vote::Yes).unwrap();
+
+        let proposal: Proposal = app.wrap().query_wasm_smart(&dao, &QueryMsg::GetProposal { proposal_id: 0 }).unwrap();
+        assert_eq!(Uint128::from(20_u128), proposal.votes_for);
+        let voters: VoterListResponse = app.wrap()
+            .query_wasm_smart(&dao, &QueryMsg::ListVoters { start_after: None, limit: None })
+            .unwrap();
+        assert_eq!(3, voters.voters.len());
+    }
+
+    fn env_at_height(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    #[test]
+    fn staked_governance_tokens_are_voting_weight() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg { governance_token: Some("uatom".to_string()), ..Default::default() };
+        let err = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+
         let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            governance_token: Some(GOVERNANCE_TOKEN.to_string()),
+            grant_denom: Some(GOVERNANCE_TOKEN.to_string()),
+            ..Default::default()
+        };
+        let err = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+
+        let msg = InstantiateMsg { governance_token: Some(GOVERNANCE_TOKEN.to_string()), ..Default::default() };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let stake = |deps: DepsMut<CoreumQueries>, height: u64, staker: &str, funds: &[Coin]| {
+            execute(deps, env_at_height(height), mock_info(staker, funds), ExecuteMsg::Stake {})
+        };
+        let err = stake(deps.as_mut(), 100, "devcore1holder_a", &coins(100, "udevcore")).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+        stake(deps.as_mut(), 100, "devcore1holder_a", &coins(100, GOVERNANCE_TOKEN)).unwrap();
+        stake(deps.as_mut(), 100, "devcore1holder_b", &coins(50, GOVERNANCE_TOKEN)).unwrap();
+
+        let propose = ExecuteMsg::from(propose_msg("Docs", None, Some(100)));
+        let err = execute(deps.as_mut(), env_at_height(101), mock_info("devcore1holder_c", &[]), propose.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NotMember { .. }));
+        execute(deps.as_mut(), env_at_height(101), mock_info("devcore1holder_a", &[]), propose).unwrap();
+
+        // Tokens staked after the proposal started do not count on it
+        stake(deps.as_mut(), 102, "devcore1holder_c", &coins(500, GOVERNANCE_TOKEN)).unwrap();
+        let vote = |deps: DepsMut<CoreumQueries>, voter: &str| {
+            execute(deps, env_at_height(102), mock_info(voter, &[]), ExecuteMsg::Vote { proposal_id: 0, vote: Vote::Yes })
+        };
+        let err = vote(deps.as_mut(), "devcore1holder_c").unwrap_err();
+        assert!(matches!(err, ContractError::NotMember { .. }));
 
+        // Unstaking after the proposal started does not take the weight away either
+        let unstake = ExecuteMsg::Unstake { amount: Uint128::from(50_u128) };
+        execute(deps.as_mut(), env_at_height(102), mock_info("devcore1holder_b", &[]), unstake).unwrap();
+        vote(deps.as_mut(), "devcore1holder_b").unwrap();
+        vote(deps.as_mut(), "devcore1holder_a").unwrap();
+
+        let proposal = load_proposal(&deps.storage, 0).unwrap();
+        assert_eq!(Uint128::from(150_u128), proposal.votes_for);
+        assert_eq!(Uint128::from(600_u128), total_weight(deps.as_ref(), None).unwrap());
+        let voter: VoterResponse = from_binary(
+            &query(deps.as_ref(), mock_env(), QueryMsg::Voter { address: "devcore1holder_b".to_string() }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(None, voter.weight);
+    }
+
+    #[test]
+    fn governance_token_is_issued_to_founding_members() {
+        let mut deps = mock_dependencies();
         let members = vec![
-            Member {
-                address: Addr::unchecked("addr1"),
-                weight: Uint128::from(10_u128),
-            },
+            MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(1_u128) },
+            MemberInit { address: "devcore1member_b".to_string(), weight: Uint128::from(2_u128) },
         ];
-        let msg = InstantiateMsg { members };
-        let info = mock_info("creator", &[]);
-        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let issue = GovernanceTokenIssue {
+            symbol: "GOV".to_string(),
+            subunit: "ugov".to_string(),
+            precision: 6,
+            description: Some("Grant DAO governance".to_string()),
+            features: vec![assetft::MINTING, assetft::FREEZING, assetft::WHITELISTING],
+            supply: Uint128::from(1000_u128),
+        };
+        let msg = InstantiateMsg { members, issue_governance_token: Some(issue), ..Default::default() };
+        let res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &coins(10, "udevcore")), msg).unwrap();
+
+        let denom = format!("ugov-{}", MOCK_CONTRACT_ADDR);
+        let expected: Vec<CosmosMsg<CoreumMsg>> = vec![
+            CoreumMsg::AssetFT(assetft::Msg::Issue {
+                symbol: "GOV".to_string(),
+                subunit: "ugov".to_string(),
+                precision: 6,
+                initial_amount: Uint128::from(1000_u128),
+                description: Some("Grant DAO governance".to_string()),
+                features: Some(vec![assetft::MINTING, assetft::FREEZING, assetft::WHITELISTING]),
+                burn_rate: None,
+                send_commission_rate: None,
+            })
+            .into(),
+            // The token is whitelisted, so each member's limit is raised to their share first
+            CoreumMsg::AssetFT(assetft::Msg::SetWhitelistedLimit {
+                account: "devcore1member_a".to_string(),
+                coin: Coin { denom: denom.clone(), amount: Uint128::from(333_u128) },
+            })
+            .into(),
+            BankMsg::Send { to_address: "devcore1member_a".to_string(), amount: coins(333, &denom) }.into(),
+            CoreumMsg::AssetFT(assetft::Msg::SetWhitelistedLimit {
+                account: "devcore1member_b".to_string(),
+                coin: Coin { denom: denom.clone(), amount: Uint128::from(666_u128) },
+            })
+            .into(),
+            BankMsg::Send { to_address: "devcore1member_b".to_string(), amount: coins(666, &denom) }.into(),
+        ];
+        assert_eq!(expected, res.messages.into_iter().map(|msg| msg.msg).collect::<Vec<_>>());
 
-        let info = mock_info("addr1", &[]);
-        let proposal_msg = ExecuteMsg::Propose {
-            title: "Another Title".to_string(),
-            description: "Another Description".to_string(),
-            amount: Some(Uint128::from(100_u128)),
-            recipient: Some(Addr::unchecked("recipient_address")),
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GovernanceToken {}).unwrap();
+        let token: GovernanceTokenResponse = from_binary(&res).unwrap();
+        assert_eq!(Some(denom.clone()), token.denom);
+
+        // Founding members vote once they stake their share
+        let info = mock_info("devcore1member_b", &coins(666, &denom));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Stake {}).unwrap();
+        let member = load_member(deps.as_ref(), &Addr::unchecked("devcore1member_b"), None).unwrap();
+        assert_eq!(Uint128::from(666_u128), member.weight);
+    }
+
+    #[test]
+    fn stakes_supplement_member_weight_and_unbond() {
+        let mut deps = mock_dependencies();
+        let members = vec![
+            MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) },
+            MemberInit { address: "devcore1member_b".to_string(), weight: Uint128::from(20_u128) },
+        ];
+        let msg = InstantiateMsg {
+            members,
+            governance_token: Some(GOVERNANCE_TOKEN.to_string()),
+            staking_mode: Some(StakingMode::Supplement),
+            unbonding_period: Some(100),
+            ..Default::default()
         };
-        execute(deps.as_mut(), mock_env(), info.clone(), proposal_msg).unwrap();
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
 
-        let vote_msg = ExecuteMsg::Vote {
-            proposal_id: 0,
-            approve: true,
+        let stake = ExecuteMsg::Stake {};
+        let info = mock_info("devcore1outsider", &coins(40, GOVERNANCE_TOKEN));
+        let err = execute(deps.as_mut(), env_at_height(100), info, stake.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NotMember { .. }));
+        let info = mock_info("devcore1member_a", &coins(40, GOVERNANCE_TOKEN));
+        execute(deps.as_mut(), env_at_height(100), info, stake).unwrap();
+        assert_eq!(Uint128::from(70_u128), total_weight(deps.as_ref(), None).unwrap());
+
+        let propose = ExecuteMsg::from(propose_msg("Docs", None, Some(100)));
+        execute(deps.as_mut(), env_at_height(101), mock_info("devcore1member_a", &[]), propose).unwrap();
+
+        let unstake = ExecuteMsg::Unstake { amount: Uint128::from(40_u128) };
+        let res = execute(deps.as_mut(), env_at_height(102), mock_info("devcore1member_a", &[]), unstake).unwrap();
+        assert!(res.messages.is_empty());
+        let member = load_member(deps.as_ref(), &Addr::unchecked("devcore1member_a"), None).unwrap();
+        assert_eq!(Uint128::from(10_u128), member.weight);
+
+        // The ballot still counts the stake held when the proposal started
+        let vote = ExecuteMsg::Vote { proposal_id: 0, vote: Vote::Yes };
+        execute(deps.as_mut(), env_at_height(102), mock_info("devcore1member_a", &[]), vote).unwrap();
+        assert_eq!(Uint128::from(50_u128), load_proposal(&deps.storage, 0).unwrap().votes_for);
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), ExecuteMsg::Claim {}).unwrap_err();
+        assert!(matches!(err, ContractError::NothingToClaim {}));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Claims { address: "devcore1member_a".to_string() }).unwrap();
+        let claims: cw_controllers::ClaimsResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::from(40_u128), claims.claims[0].amount);
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(100);
+        let res = execute(deps.as_mut(), env, mock_info("devcore1member_a", &[]), ExecuteMsg::Claim {}).unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send { to_address: "devcore1member_a".to_string(), amount: coins(40, GOVERNANCE_TOKEN) }),
+            res.messages[0].msg
+        );
+    }
+
+    #[test]
+    fn member_changes_do_not_affect_open_proposals() {
+        let mut deps = mock_dependencies();
+        let members = vec![
+            MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(30_u128) },
+            MemberInit { address: "devcore1member_b".to_string(), weight: Uint128::from(10_u128) },
+        ];
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), InstantiateMsg { members, ..Default::default() })
+            .unwrap();
+
+        let propose = |title: &str, action: Option<ProposalAction>| ExecuteMsg::from(Propose {
+            action,
+            ..propose_msg(title, None, None)
+        });
+        execute(deps.as_mut(), env_at_height(100), mock_info("devcore1member_b", &[]), propose("Open", None)).unwrap();
+
+        let update = ProposalAction::UpdateMembers {
+            add: vec![MemberInit { address: "devcore1member_c".to_string(), weight: Uint128::from(50_u128) }],
+            remove: vec!["devcore1member_b".to_string()],
         };
-        execute(deps.as_mut(), mock_env(), info.clone(), vote_msg).unwrap();
+        pass_proposal(deps.as_mut(), env_at_height(101), propose("Swap members", Some(update))).unwrap();
+        assert_eq!(Uint128::from(80_u128), total_weight(deps.as_ref(), None).unwrap());
+        assert_eq!(Uint128::from(40_u128), total_weight(deps.as_ref(), Some(100)).unwrap());
 
-        let exec_msg = ExecuteMsg::Execute { proposal_id: 0 };
-        let res = execute(deps.as_mut(), mock_env(), info, exec_msg).unwrap();
+        // The proposal opened before the change still counts the old member list
+        let vote = |deps: DepsMut<CoreumQueries>, voter: &str| {
+            execute(deps, env_at_height(102), mock_info(voter, &[]), ExecuteMsg::Vote { proposal_id: 0, vote: Vote::No })
+        };
+        let err = vote(deps.as_mut(), "devcore1member_c").unwrap_err();
+        assert!(matches!(err, ContractError::NotMember { .. }));
+        let res = vote(deps.as_mut(), "devcore1member_b").unwrap();
+        assert_eq!("10", res.events[0].attributes.iter().find(|attr| attr.key == events::ATTR_WEIGHT).unwrap().value);
+    }
+
+    #[test]
+    fn membership_nfts_follow_the_member_list() {
+        let mut deps = mock_dependencies();
+        let members = vec![
+            MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(30_u128) },
+            MemberInit { address: "devcore1member_b".to_string(), weight: Uint128::from(10_u128) },
+        ];
+        let membership_nft = NftClassInit {
+            name: "Workshop members".to_string(),
+            symbol: "wsmember".to_string(),
+            description: None,
+            uri: None,
+        };
+        let msg = InstantiateMsg { members, membership_nft: Some(membership_nft), ..Default::default() };
+        let res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let class_id = format!("wsmember-{}", MOCK_CONTRACT_ADDR);
+        // The class, then a mint and a send per member
+        assert_eq!(5, res.messages.len());
+        assert!(matches!(
+            &res.messages[0].msg,
+            CosmosMsg::Custom(CoreumMsg::AssetNFT(assetnft::Msg::IssueClass { features: Some(features), .. }))
+                if features == &vec![assetnft::BURNING, assetnft::DISABLE_SENDING]
+        ));
+        assert_eq!(
+            CosmosMsg::Custom(CoreumMsg::NFT(nft::Msg::Send {
+                class_id: class_id.clone(),
+                id: "member-1".to_string(),
+                receiver: "devcore1member_b".to_string(),
+            })),
+            res.messages[4].msg
+        );
+
+        let update = ExecuteMsg::from(Propose {
+            action: Some(ProposalAction::UpdateMembers {
+                add: vec![
+                    MemberInit { address: "devcore1member_c".to_string(), weight: Uint128::from(5_u128) },
+                    MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(40_u128) },
+                ],
+                remove: vec!["devcore1member_b".to_string()],
+            }),
+            ..propose_msg("Welcome member_c", None, None)
+        });
+        let res = pass_proposal(deps.as_mut(), mock_env(), update).unwrap();
+        let nft_msgs: Vec<_> = res.messages.iter().map(|sub| sub.msg.clone()).collect();
+        // A mint and a send for member_c, a burn for member_b and nothing for the re-weighted member_a
+        assert_eq!(3, nft_msgs.len());
+        assert!(nft_msgs.contains(&CosmosMsg::Custom(CoreumMsg::NFT(nft::Msg::Send {
+            class_id: class_id.clone(),
+            id: "member-2".to_string(),
+            receiver: "devcore1member_c".to_string(),
+        }))));
+        assert!(nft_msgs.contains(&CosmosMsg::Custom(CoreumMsg::AssetNFT(assetnft::Msg::Burn {
+            class_id: class_id.clone(),
+            id: "member-1".to_string(),
+        }))));
+        assert_eq!(Uint128::from(45_u128), TOTAL_WEIGHT.load(&deps.storage).unwrap());
+
+        let query_nft = |address: &str| -> MembershipNftResponse {
+            let msg = QueryMsg::MembershipNft { address: address.to_string() };
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+        };
+        let member_c = query_nft("devcore1member_c");
+        assert_eq!(Some(class_id), member_c.class_id);
+        assert_eq!(
+            Some(MembershipNft { id: "member-2".to_string(), role: "member".to_string(), weight: Uint128::from(5_u128) }),
+            member_c.nft
+        );
+        assert_eq!(
+            Some(MembershipNft { id: "member-0".to_string(), role: "founder".to_string(), weight: Uint128::from(40_u128) }),
+            query_nft("devcore1member_a").nft
+        );
+        assert_eq!(None, query_nft("devcore1member_b").nft);
+    }
+
+    #[test]
+    fn completed_grants_earn_a_certificate() {
+        let mut deps = mock_dependencies_with_balance(&coins(1000, "udevcore"));
+        let members = vec![MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) }];
+        let grant_certificates = NftClassInit {
+            name: "Grant certificates".to_string(),
+            symbol: "grantcert".to_string(),
+            description: None,
+            uri: None,
+        };
+        let msg = InstantiateMsg { members, grant_certificates: Some(grant_certificates), ..Default::default() };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let class_id = format!("grantcert-{}", MOCK_CONTRACT_ADDR);
+
+        let propose = |amount: Option<u128>, schedule: Option<PayoutSchedule>| ExecuteMsg::from(Propose {
+            amount: amount.map(Uint128::from),
+            schedule,
+            ..propose_msg("Block explorer", Some("devcore1grantee"), None)
+        });
+        let milestones = PayoutSchedule::Milestones {
+            reviewer: Some("devcore1reviewer".to_string()),
+            milestones: vec![
+                MilestoneInit { description: "Indexer".to_string(), amount: Uint128::from(300_u128) },
+                MilestoneInit { description: "Frontend".to_string(), amount: Uint128::from(200_u128) },
+            ],
+        };
+        pass_proposal(deps.as_mut(), mock_env(), propose(None, Some(milestones.clone()))).unwrap();
+        for index in 0..2 {
+            let submit = ExecuteMsg::SubmitMilestone { proposal_id: 0, index, deliverable: "ipfs://explorer".to_string() };
+            milestone_msg(deps.as_mut(), "devcore1grantee", submit).unwrap();
+            milestone_msg(deps.as_mut(), "devcore1reviewer", ExecuteMsg::ApproveMilestone { proposal_id: 0, index }).unwrap();
+            let res = milestone_msg(deps.as_mut(), "anyone", ExecuteMsg::ReleaseMilestone { proposal_id: 0, index }).unwrap();
+            // Only the last release completes the grant
+            assert_eq!(if index == 0 { 1 } else { 3 }, res.messages.len());
+        }
+
+        let res = pass_proposal(deps.as_mut(), mock_env(), propose(Some(100), None)).unwrap();
+        assert_eq!(
+            CosmosMsg::Custom(CoreumMsg::NFT(nft::Msg::Send {
+                class_id: class_id.clone(),
+                id: "grant-1".to_string(),
+                receiver: "devcore1grantee".to_string(),
+            })),
+            res.messages[2].msg
+        );
+
+        // A grant with a cancelled milestone was not completed, whichever settles last
+        pass_proposal(deps.as_mut(), mock_env(), propose(None, Some(milestones))).unwrap();
+        milestone_msg(deps.as_mut(), "devcore1reviewer", ExecuteMsg::CancelMilestone { proposal_id: 2, index: 1 }).unwrap();
+        let submit = ExecuteMsg::SubmitMilestone { proposal_id: 2, index: 0, deliverable: "ipfs://explorer".to_string() };
+        milestone_msg(deps.as_mut(), "devcore1grantee", submit).unwrap();
+        milestone_msg(deps.as_mut(), "devcore1reviewer", ExecuteMsg::ApproveMilestone { proposal_id: 2, index: 0 }).unwrap();
+        let res = milestone_msg(deps.as_mut(), "anyone", ExecuteMsg::ReleaseMilestone { proposal_id: 2, index: 0 }).unwrap();
         assert_eq!(1, res.messages.len());
+
+        let msg = QueryMsg::ListCertificates { recipient: "devcore1grantee".to_string() };
+        let res: CertificateListResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(Some(class_id), res.class_id);
+        let completed_at = mock_env().block.time.seconds();
+        assert_eq!(
+            vec![
+                GrantCertificate {
+                    id: "grant-0".to_string(),
+                    proposal_id: 0,
+                    recipient: Addr::unchecked("devcore1grantee"),
+                    amount: Uint128::from(500_u128),
+                    completed_at,
+                },
+                GrantCertificate {
+                    id: "grant-1".to_string(),
+                    proposal_id: 1,
+                    recipient: Addr::unchecked("devcore1grantee"),
+                    amount: Uint128::from(100_u128),
+                    completed_at,
+                },
+            ],
+            res.certificates
+        );
+    }
+
+    #[test]
+    fn smart_token_grants_check_features_before_paying() {
+        let execute_reserved_grant = |amount: u128, reserved: u128| {
+            let mut deps = mock_dependencies_with_balance(&coins(1000, GRANT_TOKEN));
+            let members = vec![MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) }];
+            let msg = InstantiateMsg { members, grant_denom: Some(GRANT_TOKEN.to_string()), ..Default::default() };
+            instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            RESERVED.save(&mut deps.storage, &Uint128::from(reserved)).unwrap();
+            let propose = ExecuteMsg::from(propose_msg("Wallet integration", Some("devcore1grantee"), Some(amount)));
+            pass_proposal(deps.as_mut(), mock_env(), propose)
+        };
+        let execute_grant = |amount: u128| execute_reserved_grant(amount, 0);
+
+        // 15% burn rate and commission come on top of the amount
+        let res = execute_grant(200).unwrap();
+        assert_eq!(res.messages[0].msg, grant_payout(GRANT_TOKEN, &Addr::unchecked("devcore1grantee"), Uint128::from(200_u128)));
+        let err = execute_grant(880).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientTreasury { required, .. } if required == Uint128::from(1012_u128)));
+        let err = execute_grant(800).unwrap_err();
+        assert!(matches!(err, ContractError::TreasuryFrozen { spendable, .. } if spendable == Uint128::from(900_u128)));
+        // What is not frozen may still be reserved for other grants
+        let err = execute_reserved_grant(560, 300).unwrap_err();
+        assert!(matches!(err, ContractError::TreasuryFrozen { spendable, .. } if spendable == Uint128::from(600_u128)));
+        let err = execute_grant(260).unwrap_err();
+        assert!(matches!(err, ContractError::WhitelistLimit { allowed, .. } if allowed == Uint128::from(250_u128)));
+    }
+
+    #[test]
+    fn smart_token_reservations_cover_fees() {
+        let setup = || {
+            let mut deps = mock_dependencies_with_balance(&coins(1000, GRANT_TOKEN));
+            let members = vec![MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) }];
+            let msg = InstantiateMsg { members, grant_denom: Some(GRANT_TOKEN.to_string()), ..Default::default() };
+            instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            let propose = ExecuteMsg::from(Propose {
+                schedule: Some(PayoutSchedule::Milestones {
+                    reviewer: Some("devcore1reviewer".to_string()),
+                    milestones: vec![
+                        MilestoneInit { description: "Indexer".to_string(), amount: Uint128::from(200_u128) },
+                        MilestoneInit { description: "Frontend".to_string(), amount: Uint128::from(260_u128) },
+                    ],
+                }),
+                ..propose_msg("Block explorer", Some("devcore1grantee"), None)
+            });
+            pass_proposal(deps.as_mut(), mock_env(), propose).unwrap();
+            deps
+        };
+        let mut deps = setup();
+        // 230 + 299, burn rate and commission included
+        assert_eq!(Uint128::from(529_u128), RESERVED.load(&deps.storage).unwrap());
+
+        for index in 0..2 {
+            let submit = ExecuteMsg::SubmitMilestone { proposal_id: 0, index, deliverable: "https://example.com".to_string() };
+            milestone_msg(deps.as_mut(), "devcore1grantee", submit).unwrap();
+            milestone_msg(deps.as_mut(), "devcore1reviewer", ExecuteMsg::ApproveMilestone { proposal_id: 0, index }).unwrap();
+        }
+        let res = milestone_msg(deps.as_mut(), "anyone", ExecuteMsg::ReleaseMilestone { proposal_id: 0, index: 0 }).unwrap();
+        assert_eq!(res.messages[0].msg, grant_payout(GRANT_TOKEN, &Addr::unchecked("devcore1grantee"), Uint128::from(200_u128)));
+        assert_eq!(Uint128::from(299_u128), RESERVED.load(&deps.storage).unwrap());
+
+        // Releases are checked like lump sums, the grantee may only receive 250 more
+        let err = milestone_msg(deps.as_mut(), "anyone", ExecuteMsg::ReleaseMilestone { proposal_id: 0, index: 1 }).unwrap_err();
+        assert!(matches!(err, ContractError::WhitelistLimit { allowed, .. } if allowed == Uint128::from(250_u128)));
+
+        // The issuer pays no fees, so seen from its address the rate has dropped to zero. Settling the
+        // grant still releases exactly the 529 reserved, not the 460 its milestones would now take.
+        let mut deps = setup();
+        let mut env = mock_env();
+        env.contract.address = Addr::unchecked("devcore1issuer");
+        for index in 0..2 {
+            let cancel = ExecuteMsg::CancelMilestone { proposal_id: 0, index };
+            execute(deps.as_mut(), env.clone(), mock_info("devcore1grantee", &[]), cancel).unwrap();
+        }
+        assert_eq!(Uint128::zero(), RESERVED.load(&deps.storage).unwrap());
+        assert_eq!(Uint128::zero(), load_proposal(&deps.storage, 0).unwrap().reserved);
     }
-    
-}
\ No newline at end of file
+
+    #[test]
+    fn dao_administers_its_smart_token() {
+        let mut deps = mock_dependencies();
+        let members = vec![MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) }];
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), InstantiateMsg { members, ..Default::default() }).unwrap();
+        let administer = |msg: TokenAdminMsg| ExecuteMsg::from(Propose {
+            action: Some(ProposalAction::AdministerToken { msg }),
+            ..propose_msg("Token admin", None, None)
+        });
+
+        let freeze = TokenAdminMsg::Freeze { account: "devcore1holder".to_string(), coin: Coin::new(500, DAO_TOKEN) };
+        let res = pass_proposal(deps.as_mut(), mock_env(), administer(freeze)).unwrap();
+        assert_eq!(
+            CosmosMsg::Custom(CoreumMsg::AssetFT(assetft::Msg::Freeze {
+                account: "devcore1holder".to_string(),
+                coin: Coin::new(500, DAO_TOKEN),
+            })),
+            res.messages[0].msg
+        );
+
+        // The token has no whitelisting, and the governance token is administered by someone else
+        let member = mock_info("devcore1member_a", &[]);
+        let limit = TokenAdminMsg::SetWhitelistedLimit { account: "devcore1holder".to_string(), coin: Coin::new(1, DAO_TOKEN) };
+        let err = execute(deps.as_mut(), mock_env(), member.clone(), administer(limit)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(reason) if reason.contains("whitelisting")));
+        let mint = TokenAdminMsg::Mint { coin: Coin::new(1000, GOVERNANCE_TOKEN) };
+        let err = execute(deps.as_mut(), mock_env(), member, administer(mint)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(reason) if reason.contains("does not administer")));
+    }
+
+    #[test]
+    fn cw20_deposits_pay_cw20_grants() {
+        let mut deps = mock_dependencies();
+        let members = vec![MemberInit { address: "devcore1member_a".to_string(), weight: Uint128::from(10_u128) }];
+        let msg = InstantiateMsg { members, cw20_tokens: Some(vec!["devcore1cw20token".to_string()]), ..Default::default() };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let deposit = |amount: u128| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "devcore1donor".to_string(),
+                amount: Uint128::from(amount),
+                msg: Binary::default(),
+            })
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1othertoken", &[]), deposit(500)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+        execute(deps.as_mut(), mock_env(), mock_info("devcore1cw20token", &[]), deposit(500)).unwrap();
+
+        let propose = |contract: &str| ExecuteMsg::from(Propose {
+            schedule: Some(PayoutSchedule::Cw20 { contract: contract.to_string() }),
+            ..propose_msg("Bridge audit", Some("devcore1auditor"), Some(300))
+        });
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), propose("devcore1othertoken")).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+        let res = pass_proposal(deps.as_mut(), mock_env(), propose("devcore1cw20token")).unwrap();
+        assert_eq!(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "devcore1cw20token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer { recipient: "devcore1auditor".to_string(), amount: Uint128::from(300_u128) })
+                    .unwrap(),
+                funds: vec![],
+            }),
+            res.messages[0].msg
+        );
+
+        let res: Cw20BalancesResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Cw20Balances {}).unwrap()).unwrap();
+        assert_eq!(vec![Cw20Coin { address: "devcore1cw20token".to_string(), amount: Uint128::from(200_u128) }], res.balances);
+
+        // Paid in full at execution, nothing is left to claw back
+        let clawback = ExecuteMsg::from(Propose {
+            action: Some(ProposalAction::Clawback { proposal_id: 0, reason: "Missed deadline".to_string() }),
+            ..propose_msg("Claw back the audit", None, None)
+        });
+        let err = execute(deps.as_mut(), mock_env(), mock_info("devcore1member_a", &[]), clawback).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidInput(_)));
+    }
+}
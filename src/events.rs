@@ -0,0 +1,405 @@
+//! Typed events for indexers. CosmWasm prefixes every event type with `wasm-`, so a proposal
+//! created by this contract shows up as `wasm-grant_dao_proposal_created`. Amounts are in the
+//! grant denom and timestamps are UNIX seconds.
+
+use cosmwasm_std::{to_vec, Addr, Event, StdResult, Uint128};
+use cw3::Vote;
+use serde::Serialize;
+
+use crate::state::{
+    ApplicationStatus, GrantApplication, Milestone, MilestoneStatus, Payout, Proposal, ProposalAction, ProposalStatus,
+    TallyMethod, TokenAdminMsg,
+};
+
+/// A proposal was stored.
+/// Attributes: proposal_id, proposer, recipient, amount, payout, action, status, voting_end
+pub const PROPOSAL_CREATED: &str = "grant_dao_proposal_created";
+/// A member voted.
+/// Attributes: proposal_id, voter, option, weight, votes_for, votes_against, votes_abstain
+pub const VOTE_CAST: &str = "grant_dao_vote_cast";
+/// A proposal moved to another status, see `status_name` for the values.
+/// Attributes: proposal_id, status, plus reason for vetoes and clawbacks, by for vetoes and
+/// voting_end when voting opens
+pub const PROPOSAL_STATUS_CHANGED: &str = "grant_dao_proposal_status_changed";
+/// A passed proposal was executed, it cannot be executed again.
+/// Attributes: proposal_id, recipient, amount, payout, action, votes_for, votes_against
+pub const PROPOSAL_EXECUTED: &str = "grant_dao_proposal_executed";
+/// Funds of a grant left the treasury.
+/// Attributes: proposal_id, recipient, amount, payout
+pub const GRANT_PAID: &str = "grant_dao_grant_paid";
+/// An escrowed grant stopped paying, what was left returned to the treasury.
+/// Attributes: proposal_id, reason, reclaimed
+pub const GRANT_ENDED: &str = "grant_dao_grant_ended";
+/// A member joined, left or changed weight, a weight of 0 means the member was removed.
+/// Attributes: address, weight
+pub const MEMBER_CHANGED: &str = "grant_dao_member_changed";
+/// A config field changed.
+/// Attributes: key, value (the new value as JSON)
+pub const CONFIG_CHANGED: &str = "grant_dao_config_changed";
+/// CW20 tokens were sent to the treasury.
+/// Attributes: token, sender, amount
+pub const CW20_DEPOSITED: &str = "grant_dao_cw20_deposited";
+/// Unstaked governance tokens were paid out after the unbonding period.
+/// Attributes: address, amount
+pub const STAKE_CLAIMED: &str = "grant_dao_stake_claimed";
+/// An address gained or lost the reviewer role.
+/// Attributes: address, added
+pub const REVIEWER_CHANGED: &str = "grant_dao_reviewer_changed";
+/// Reviewers were assigned to a proposal.
+/// Attributes: proposal_id, reviewers (comma separated, all assigned so far)
+pub const REVIEWERS_ASSIGNED: &str = "grant_dao_reviewers_assigned";
+/// A reviewer scored a proposal.
+/// Attributes: proposal_id, reviewer, score, review_count
+pub const REVIEW_SUBMITTED: &str = "grant_dao_review_submitted";
+/// An address was added to or removed from the recipient allow-list or block-list.
+/// Attributes: list (allowlist or blocklist), address, added
+pub const RECIPIENT_LIST_CHANGED: &str = "grant_dao_recipient_list_changed";
+/// A milestone was submitted, approved, released or cancelled, see `milestone_status_name`.
+/// Attributes: proposal_id, index, status, approvals
+pub const MILESTONE_CHANGED: &str = "grant_dao_milestone_changed";
+/// A grant application was filed, sponsored or expired, see `application_status_name`.
+/// Attributes: application_id, applicant, recipient, requested, status, plus proposal_id once sponsored
+pub const APPLICATION_CHANGED: &str = "grant_dao_application_changed";
+/// The DAO administered its smart token.
+/// Attributes: denom, operation
+pub const TOKEN_ADMINISTERED: &str = "grant_dao_token_administered";
+/// A grant round or quadratic funding round opened, its budget is reserved.
+/// Attributes: round_id, kind (approval, ranked_choice or quadratic), budget, applications, round_end
+pub const ROUND_CREATED: &str = "grant_dao_round_created";
+/// A member voted in a grant round.
+/// Attributes: round_id, voter, choices (comma separated), weight
+pub const ROUND_VOTE_CAST: &str = "grant_dao_round_vote_cast";
+/// Someone contributed to an application of a quadratic funding round.
+/// Attributes: round_id, application, contributor, amount
+pub const CONTRIBUTION_MADE: &str = "grant_dao_contribution_made";
+/// A round was finalized and paid out.
+/// Attributes: round_id, kind, paid, plus winners (comma separated) for grant rounds and matched for
+/// quadratic funding rounds
+pub const ROUND_FINALIZED: &str = "grant_dao_round_finalized";
+
+pub const ATTR_PROPOSAL_ID: &str = "proposal_id";
+pub const ATTR_PROPOSER: &str = "proposer";
+pub const ATTR_RECIPIENT: &str = "recipient";
+pub const ATTR_AMOUNT: &str = "amount";
+pub const ATTR_PAYOUT: &str = "payout";
+pub const ATTR_ACTION: &str = "action";
+pub const ATTR_STATUS: &str = "status";
+pub const ATTR_VOTING_END: &str = "voting_end";
+pub const ATTR_VOTER: &str = "voter";
+pub const ATTR_OPTION: &str = "option";
+pub const ATTR_WEIGHT: &str = "weight";
+pub const ATTR_VOTES_FOR: &str = "votes_for";
+pub const ATTR_VOTES_AGAINST: &str = "votes_against";
+pub const ATTR_VOTES_ABSTAIN: &str = "votes_abstain";
+pub const ATTR_BY: &str = "by";
+pub const ATTR_REASON: &str = "reason";
+pub const ATTR_RECLAIMED: &str = "reclaimed";
+pub const ATTR_ADDRESS: &str = "address";
+pub const ATTR_KEY: &str = "key";
+pub const ATTR_VALUE: &str = "value";
+pub const ATTR_TOKEN: &str = "token";
+pub const ATTR_SENDER: &str = "sender";
+pub const ATTR_ADDED: &str = "added";
+pub const ATTR_REVIEWER: &str = "reviewer";
+pub const ATTR_REVIEWERS: &str = "reviewers";
+pub const ATTR_SCORE: &str = "score";
+pub const ATTR_REVIEW_COUNT: &str = "review_count";
+pub const ATTR_LIST: &str = "list";
+pub const ATTR_INDEX: &str = "index";
+pub const ATTR_APPROVALS: &str = "approvals";
+pub const ATTR_APPLICATION_ID: &str = "application_id";
+pub const ATTR_APPLICANT: &str = "applicant";
+pub const ATTR_REQUESTED: &str = "requested";
+pub const ATTR_DENOM: &str = "denom";
+pub const ATTR_OPERATION: &str = "operation";
+pub const ATTR_ROUND_ID: &str = "round_id";
+pub const ATTR_KIND: &str = "kind";
+pub const ATTR_BUDGET: &str = "budget";
+pub const ATTR_APPLICATIONS: &str = "applications";
+pub const ATTR_ROUND_END: &str = "round_end";
+pub const ATTR_CHOICES: &str = "choices";
+pub const ATTR_APPLICATION: &str = "application";
+pub const ATTR_CONTRIBUTOR: &str = "contributor";
+pub const ATTR_PAID: &str = "paid";
+pub const ATTR_WINNERS: &str = "winners";
+pub const ATTR_MATCHED: &str = "matched";
+
+/// The `payout` attribute: lump_sum, milestones, vesting, recurring or cw20
+pub fn payout_name(payout: &Payout) -> &'static str {
+    match payout {
+        Payout::LumpSum => "lump_sum",
+        Payout::Milestones { .. } => "milestones",
+        Payout::Vesting { .. } => "vesting",
+        Payout::Recurring { .. } => "recurring",
+        Payout::Cw20 { .. } => "cw20",
+    }
+}
+
+/// The `status` attribute: in_review, open, clawed_back, vetoed or rejected
+pub fn status_name(status: &ProposalStatus) -> &'static str {
+    match status {
+        ProposalStatus::InReview => "in_review",
+        ProposalStatus::Open => "open",
+        ProposalStatus::ClawedBack => "clawed_back",
+        ProposalStatus::Vetoed { .. } => "vetoed",
+        ProposalStatus::Rejected => "rejected",
+    }
+}
+
+/// The `action` attribute, `none` for grant proposals
+pub fn action_name(action: &Option<ProposalAction>) -> &'static str {
+    match action {
+        None => "none",
+        Some(ProposalAction::TerminateStream { .. }) => "terminate_stream",
+        Some(ProposalAction::StopRecurring { .. }) => "stop_recurring",
+        Some(ProposalAction::UpdateReviewers { .. }) => "update_reviewers",
+        Some(ProposalAction::Clawback { .. }) => "clawback",
+        Some(ProposalAction::UpdateRecipientLists { .. }) => "update_recipient_lists",
+        Some(ProposalAction::SetAllowlistMode { .. }) => "set_allowlist_mode",
+        Some(ProposalAction::SetCompliance { .. }) => "set_compliance",
+        Some(ProposalAction::SetContentLimits { .. }) => "set_content_limits",
+        Some(ProposalAction::SetGuardian { .. }) => "set_guardian",
+        Some(ProposalAction::SetVetoCouncil { .. }) => "set_veto_council",
+        Some(ProposalAction::UpdateMembers { .. }) => "update_members",
+        Some(ProposalAction::UpdateCw20Tokens { .. }) => "update_cw20_tokens",
+        Some(ProposalAction::AdministerToken { .. }) => "administer_token",
+        Some(ProposalAction::CreateRound { .. }) => "create_round",
+        Some(ProposalAction::CreateQuadraticRound { .. }) => "create_quadratic_round",
+    }
+}
+
+/// The milestone `status` attribute: pending, submitted, approved, released or cancelled
+pub fn milestone_status_name(status: &MilestoneStatus) -> &'static str {
+    match status {
+        MilestoneStatus::Pending => "pending",
+        MilestoneStatus::Submitted => "submitted",
+        MilestoneStatus::Approved => "approved",
+        MilestoneStatus::Released => "released",
+        MilestoneStatus::Cancelled => "cancelled",
+    }
+}
+
+/// The application `status` attribute: pending, sponsored or expired
+pub fn application_status_name(status: &ApplicationStatus) -> &'static str {
+    match status {
+        ApplicationStatus::Pending => "pending",
+        ApplicationStatus::Sponsored { .. } => "sponsored",
+        ApplicationStatus::Expired => "expired",
+    }
+}
+
+/// The `operation` attribute: mint, burn, freeze, unfreeze, globally_freeze, globally_unfreeze or
+/// set_whitelisted_limit
+pub fn token_operation_name(msg: &TokenAdminMsg) -> &'static str {
+    match msg {
+        TokenAdminMsg::Mint { .. } => "mint",
+        TokenAdminMsg::Burn { .. } => "burn",
+        TokenAdminMsg::Freeze { .. } => "freeze",
+        TokenAdminMsg::Unfreeze { .. } => "unfreeze",
+        TokenAdminMsg::GloballyFreeze { .. } => "globally_freeze",
+        TokenAdminMsg::GloballyUnfreeze { .. } => "globally_unfreeze",
+        TokenAdminMsg::SetWhitelistedLimit { .. } => "set_whitelisted_limit",
+    }
+}
+
+/// The round `kind` attribute: approval, ranked_choice, or quadratic for quadratic funding rounds
+pub fn tally_method_name(method: &TallyMethod) -> &'static str {
+    match method {
+        TallyMethod::Approval => "approval",
+        TallyMethod::RankedChoice => "ranked_choice",
+    }
+}
+
+pub const QUADRATIC_KIND: &str = "quadratic";
+
+/// Comma separated value for list attributes
+pub fn join<T: ToString>(items: impl IntoIterator<Item = T>) -> String {
+    items.into_iter().map(|item| item.to_string()).collect::<Vec<_>>().join(",")
+}
+
+pub fn proposal_created(proposal: &Proposal, proposer: &Addr) -> Event {
+    Event::new(PROPOSAL_CREATED)
+        .add_attribute(ATTR_PROPOSAL_ID, proposal.id.to_string())
+        .add_attribute(ATTR_PROPOSER, proposer.to_string())
+        .add_attribute(ATTR_RECIPIENT, proposal.recipient.to_string())
+        .add_attribute(ATTR_AMOUNT, proposal.amount.to_string())
+        .add_attribute(ATTR_PAYOUT, payout_name(&proposal.payout))
+        .add_attribute(ATTR_ACTION, action_name(&proposal.action))
+        .add_attribute(ATTR_STATUS, status_name(&proposal.status))
+        .add_attribute(ATTR_VOTING_END, proposal.voting_end.to_string())
+}
+
+/// The `option` attribute: yes, no, abstain or veto
+pub fn vote_option(vote: Vote) -> &'static str {
+    match vote {
+        Vote::Yes => "yes",
+        Vote::No => "no",
+        Vote::Abstain => "abstain",
+        Vote::Veto => "veto",
+    }
+}
+
+pub fn vote_cast(proposal: &Proposal, voter: &Addr, vote: Vote, weight: Uint128) -> Event {
+    Event::new(VOTE_CAST)
+        .add_attribute(ATTR_PROPOSAL_ID, proposal.id.to_string())
+        .add_attribute(ATTR_VOTER, voter.to_string())
+        .add_attribute(ATTR_OPTION, vote_option(vote))
+        .add_attribute(ATTR_WEIGHT, weight.to_string())
+        .add_attribute(ATTR_VOTES_FOR, proposal.votes_for.to_string())
+        .add_attribute(ATTR_VOTES_AGAINST, proposal.votes_against.to_string())
+        .add_attribute(ATTR_VOTES_ABSTAIN, proposal.votes_abstain.to_string())
+}
+
+pub fn proposal_status_changed(proposal: &Proposal) -> Event {
+    let event = Event::new(PROPOSAL_STATUS_CHANGED)
+        .add_attribute(ATTR_PROPOSAL_ID, proposal.id.to_string())
+        .add_attribute(ATTR_STATUS, status_name(&proposal.status));
+    match &proposal.status {
+        ProposalStatus::Vetoed { by, reason } => event
+            .add_attribute(ATTR_BY, by.to_string())
+            .add_attribute(ATTR_REASON, reason),
+        _ => event,
+    }
+}
+
+pub fn proposal_executed(proposal: &Proposal) -> Event {
+    Event::new(PROPOSAL_EXECUTED)
+        .add_attribute(ATTR_PROPOSAL_ID, proposal.id.to_string())
+        .add_attribute(ATTR_RECIPIENT, proposal.recipient.to_string())
+        .add_attribute(ATTR_AMOUNT, proposal.amount.to_string())
+        .add_attribute(ATTR_PAYOUT, payout_name(&proposal.payout))
+        .add_attribute(ATTR_ACTION, action_name(&proposal.action))
+        .add_attribute(ATTR_VOTES_FOR, proposal.votes_for.to_string())
+        .add_attribute(ATTR_VOTES_AGAINST, proposal.votes_against.to_string())
+}
+
+pub fn grant_paid(proposal: &Proposal, amount: Uint128) -> Event {
+    Event::new(GRANT_PAID)
+        .add_attribute(ATTR_PROPOSAL_ID, proposal.id.to_string())
+        .add_attribute(ATTR_RECIPIENT, proposal.recipient.to_string())
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+        .add_attribute(ATTR_PAYOUT, payout_name(&proposal.payout))
+}
+
+/// `reason` is terminated, stopped or clawed_back
+pub fn grant_ended(proposal_id: u64, reason: &str, reclaimed: Uint128) -> Event {
+    Event::new(GRANT_ENDED)
+        .add_attribute(ATTR_PROPOSAL_ID, proposal_id.to_string())
+        .add_attribute(ATTR_REASON, reason)
+        .add_attribute(ATTR_RECLAIMED, reclaimed.to_string())
+}
+
+pub fn member_changed(address: &Addr, weight: Uint128) -> Event {
+    Event::new(MEMBER_CHANGED)
+        .add_attribute(ATTR_ADDRESS, address.to_string())
+        .add_attribute(ATTR_WEIGHT, weight.to_string())
+}
+
+pub fn config_changed<T: Serialize>(key: &str, value: &T) -> StdResult<Event> {
+    let value = String::from_utf8_lossy(&to_vec(value)?).into_owned();
+    Ok(Event::new(CONFIG_CHANGED)
+        .add_attribute(ATTR_KEY, key)
+        .add_attribute(ATTR_VALUE, value))
+}
+
+pub fn cw20_deposited(token: &Addr, sender: &str, amount: Uint128) -> Event {
+    Event::new(CW20_DEPOSITED)
+        .add_attribute(ATTR_TOKEN, token.to_string())
+        .add_attribute(ATTR_SENDER, sender)
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+}
+
+pub fn stake_claimed(address: &Addr, amount: Uint128) -> Event {
+    Event::new(STAKE_CLAIMED)
+        .add_attribute(ATTR_ADDRESS, address.to_string())
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+}
+
+pub fn reviewer_changed(address: &Addr, added: bool) -> Event {
+    Event::new(REVIEWER_CHANGED)
+        .add_attribute(ATTR_ADDRESS, address.to_string())
+        .add_attribute(ATTR_ADDED, added.to_string())
+}
+
+pub fn reviewers_assigned(proposal: &Proposal) -> Event {
+    Event::new(REVIEWERS_ASSIGNED)
+        .add_attribute(ATTR_PROPOSAL_ID, proposal.id.to_string())
+        .add_attribute(ATTR_REVIEWERS, join(&proposal.reviewers))
+}
+
+pub fn review_submitted(proposal: &Proposal, reviewer: &Addr, score: u8) -> Event {
+    Event::new(REVIEW_SUBMITTED)
+        .add_attribute(ATTR_PROPOSAL_ID, proposal.id.to_string())
+        .add_attribute(ATTR_REVIEWER, reviewer.to_string())
+        .add_attribute(ATTR_SCORE, score.to_string())
+        .add_attribute(ATTR_REVIEW_COUNT, proposal.review_count.to_string())
+}
+
+/// `list` is allowlist or blocklist
+pub fn recipient_list_changed(list: &str, address: &str, added: bool) -> Event {
+    Event::new(RECIPIENT_LIST_CHANGED)
+        .add_attribute(ATTR_LIST, list)
+        .add_attribute(ATTR_ADDRESS, address)
+        .add_attribute(ATTR_ADDED, added.to_string())
+}
+
+pub fn milestone_changed(proposal_id: u64, index: u32, milestone: &Milestone) -> Event {
+    Event::new(MILESTONE_CHANGED)
+        .add_attribute(ATTR_PROPOSAL_ID, proposal_id.to_string())
+        .add_attribute(ATTR_INDEX, index.to_string())
+        .add_attribute(ATTR_STATUS, milestone_status_name(&milestone.status))
+        .add_attribute(ATTR_APPROVALS, milestone.approvals.len().to_string())
+}
+
+pub fn application_changed(application: &GrantApplication) -> Event {
+    let event = Event::new(APPLICATION_CHANGED)
+        .add_attribute(ATTR_APPLICATION_ID, application.id.to_string())
+        .add_attribute(ATTR_APPLICANT, application.applicant.to_string())
+        .add_attribute(ATTR_RECIPIENT, application.recipient.to_string())
+        .add_attribute(ATTR_REQUESTED, application.requested.to_string())
+        .add_attribute(ATTR_STATUS, application_status_name(&application.status));
+    match application.status {
+        ApplicationStatus::Sponsored { proposal_id } => event.add_attribute(ATTR_PROPOSAL_ID, proposal_id.to_string()),
+        _ => event,
+    }
+}
+
+pub fn token_administered(msg: &TokenAdminMsg) -> Event {
+    Event::new(TOKEN_ADMINISTERED)
+        .add_attribute(ATTR_DENOM, msg.denom())
+        .add_attribute(ATTR_OPERATION, token_operation_name(msg))
+}
+
+/// `kind` is approval, ranked_choice or quadratic
+pub fn round_created(round_id: u64, kind: &str, budget: Uint128, applications: usize, round_end: u64) -> Event {
+    Event::new(ROUND_CREATED)
+        .add_attribute(ATTR_ROUND_ID, round_id.to_string())
+        .add_attribute(ATTR_KIND, kind)
+        .add_attribute(ATTR_BUDGET, budget.to_string())
+        .add_attribute(ATTR_APPLICATIONS, applications.to_string())
+        .add_attribute(ATTR_ROUND_END, round_end.to_string())
+}
+
+pub fn round_vote_cast(round_id: u64, voter: &Addr, choices: &[u32], weight: Uint128) -> Event {
+    Event::new(ROUND_VOTE_CAST)
+        .add_attribute(ATTR_ROUND_ID, round_id.to_string())
+        .add_attribute(ATTR_VOTER, voter.to_string())
+        .add_attribute(ATTR_CHOICES, join(choices))
+        .add_attribute(ATTR_WEIGHT, weight.to_string())
+}
+
+pub fn contribution_made(round_id: u64, application: u32, contributor: &Addr, amount: Uint128) -> Event {
+    Event::new(CONTRIBUTION_MADE)
+        .add_attribute(ATTR_ROUND_ID, round_id.to_string())
+        .add_attribute(ATTR_APPLICATION, application.to_string())
+        .add_attribute(ATTR_CONTRIBUTOR, contributor.to_string())
+        .add_attribute(ATTR_AMOUNT, amount.to_string())
+}
+
+/// Add winners or matched to the returned event
+pub fn round_finalized(round_id: u64, kind: &str, paid: Uint128) -> Event {
+    Event::new(ROUND_FINALIZED)
+        .add_attribute(ATTR_ROUND_ID, round_id.to_string())
+        .add_attribute(ATTR_KIND, kind)
+        .add_attribute(ATTR_PAID, paid.to_string())
+}
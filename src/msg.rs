@@ -1,41 +1,360 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use cosmwasm_std::{Addr, Uint128};
-use crate::state::{Member};
+use coreum_wasm_sdk::core::CoreumMsg;
+use cosmwasm_std::{CosmosMsg, Decimal, Uint128};
+use cw3::{ProposalResponse, Vote};
+use cw20::{Cw20Coin, Cw20ReceiveMsg};
+use cw4::MemberChangedHookMsg;
+use cw_utils::Expiration;
+use crate::state::{ContentLimits, GrantCertificate, MembershipNft, ProposalAction, RecipientListUpdate, StakingMode, VetoCouncilInit};
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
 #[serde(rename_all = "snake_case")]
 pub struct InstantiateMsg {
-    pub members: Vec<Member>,
+    pub members: Vec<MemberInit>,
+    pub application_fee: Option<Uint128>, // defaults to no fee
+    pub application_period: Option<u64>, // seconds, defaults to the voting period
+    pub burn_expired_fees: Option<bool>, // defaults to returning the fee
+    pub reviewers: Option<Vec<String>>,
+    pub min_reviews: Option<u32>, // defaults to voting without reviews
+    pub allowlist_enabled: Option<bool>,
+    pub compliance: Option<String>,
+    pub content_limits: Option<ContentLimits>,
+    pub guardian: Option<String>,
+    pub timelock: Option<u64>, // seconds, defaults to executing as soon as a proposal passes
+    pub quorum: Option<Decimal>, // share of the total weight that has to vote, defaults to no quorum
+    pub veto_council: Option<VetoCouncilInit>, // requires a timelock
+    pub group: Option<String>, // cw4 group contract holding the membership instead of `members`
+    pub governance_token: Option<String>, // AssetFT denom staked for voting weight instead of `members`
+    pub issue_governance_token: Option<GovernanceTokenIssue>, // issues a new governance token instead
+    pub staking_mode: Option<StakingMode>, // defaults to the stake replacing `members`
+    pub unbonding_period: Option<u64>, // seconds, defaults to the voting period
+    pub membership_nft: Option<NftClassInit>, // mints a soulbound NFT to every member
+    pub grant_certificates: Option<NftClassInit>, // mints a certificate NFT for every completed grant
+    pub grant_denom: Option<String>, // native denom or AssetFT smart token, defaults to udevcore
+    pub cw20_tokens: Option<Vec<String>>, // CW20 contracts the treasury accepts and grants can pay
+}
+
+/// An AssetNFT class issued by the DAO, its id is `{symbol}-{dao address}`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NftClassInit {
+    pub name: String,
+    pub symbol: String,
+    pub description: Option<String>,
+    pub uri: Option<String>,
+}
+
+/// A new AssetFT governance token issued by the DAO, `supply` is split between the founding
+/// `members` by weight. The AssetFT issue fee has to be sent along with the instantiation.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GovernanceTokenIssue {
+    pub symbol: String,
+    pub subunit: String,
+    pub precision: u32,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub features: Vec<u32>, // coreum_wasm_sdk::assetft feature constants, e.g. FREEZING or WHITELISTING
+    pub supply: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MemberInit {
+    pub address: String,
+    pub weight: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
+    /// cw3 compatible, `recipient` and the fields after it are grant extensions
     Propose {
         title: String,
         description: String,
-        recipient: Option<Addr>,
+        #[serde(default)]
+        msgs: Vec<CosmosMsg<CoreumMsg>>,
+        latest: Option<Expiration>, // a time, defaults to the full voting period
+        recipient: Option<String>,
         amount: Option<Uint128>,
+        schedule: Option<PayoutSchedule>,
+        action: Option<ProposalAction>,
+        url: Option<String>,
+        content_hash: Option<String>, // hex encoded sha256
     },
     Vote {
         proposal_id: u64,
-        approve: bool,
+        vote: Vote,
     },
     Execute {
         proposal_id: u64,
     },
+    /// Marks a proposal whose vote failed as rejected
+    Close {
+        proposal_id: u64,
+    },
+    /// Sent by the cw4 group when its membership changes, register the DAO with the group's `AddHook`
+    MemberChangedHook(MemberChangedHookMsg),
+    VoteRound {
+        round_id: u64,
+        choices: Vec<u32>,
+    },
+    FinalizeRound {
+        round_id: u64,
+    },
+    Contribute {
+        round_id: u64,
+        application: u32,
+    },
+    FinalizeQuadraticRound {
+        round_id: u64,
+    },
+    SubmitMilestone {
+        proposal_id: u64,
+        index: u32,
+        deliverable: String,
+    },
+    ApproveMilestone {
+        proposal_id: u64,
+        index: u32,
+    },
+    ReleaseMilestone {
+        proposal_id: u64,
+        index: u32,
+    },
+    CancelMilestone {
+        proposal_id: u64,
+        index: u32,
+    },
+    ClaimVested {
+        proposal_id: u64,
+    },
+    ClaimRecurring {
+        proposal_id: u64,
+    },
+    Apply {
+        title: String,
+        description: String,
+        requested: Uint128,
+        recipient: Option<String>,
+    },
+    Sponsor {
+        application_id: u64,
+    },
+    ExpireApplication {
+        application_id: u64,
+    },
+    AssignReviewers {
+        proposal_id: u64,
+        reviewers: Vec<String>,
+    },
+    SubmitReview {
+        proposal_id: u64,
+        score: u8,
+        comment_hash: String,
+    },
+    /// Only for the compliance role, the DAO uses ProposalAction::UpdateRecipientLists
+    UpdateRecipientLists {
+        update: RecipientListUpdate,
+    },
+    /// Guardian only: blocks payouts until the given UNIX timestamp
+    Pause {
+        until: u64,
+    },
+    /// Guardian only
+    Unpause {},
+    /// Veto council only, during the timelock of a passed proposal
+    Veto {
+        proposal_id: u64,
+        reason: String,
+    },
+    /// Stakes the governance token sent along, the stake is the voting weight
+    Stake {},
+    /// Starts unbonding staked governance tokens, their weight is gone straight away
+    Unstake {
+        amount: Uint128,
+    },
+    /// Returns unstaked tokens whose unbonding period is over
+    Claim {},
+    /// Sent by an accepted CW20 contract when tokens are sent to the DAO, records the deposit
+    Receive(Cw20ReceiveMsg),
+}
+
+/// Pays a grant in parts instead of a single transfer on execution
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutSchedule {
+    Milestones {
+        /// Approves deliverables on behalf of the members when set
+        reviewer: Option<String>,
+        milestones: Vec<MilestoneInit>,
+    },
+    Vesting {
+        start: u64, // UNIX timestamp
+        end: u64, // UNIX timestamp
+        cliff: Option<u64>, // UNIX timestamp
+    },
+    Recurring {
+        period_amount: Uint128,
+        interval: u64, // seconds
+        periods: u32,
+    },
+    /// Pays the amount at once in an accepted CW20 token instead of the grant denom
+    Cw20 {
+        contract: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoundApplicationInit {
+    pub title: String,
+    pub description: String,
+    pub recipient: String,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FundingApplicationInit {
+    pub title: String,
+    pub description: String,
+    pub recipient: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MilestoneInit {
+    pub description: String,
+    pub amount: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
+    // The cw3 queries, answered with the cw3 response types
+    Threshold {},
+    Proposal {
+        proposal_id: u64,
+    },
+    ListProposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    ReverseProposals {
+        start_before: Option<u64>,
+        limit: Option<u32>,
+    },
+    Vote {
+        proposal_id: u64,
+        voter: String,
+    },
+    ListVotes {
+        proposal_id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    Voter {
+        address: String,
+    },
+    ListVoters {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // Grant DAO extensions
+    /// The full proposal, including its payout schedule and reviews
     GetProposal {
         proposal_id: u64,
     },
-    ListProposals {},
     GetMember {
-        address: Addr,
+        address: String,
     },
     ListMembers {},
+    GetRound {
+        round_id: u64,
+    },
+    ListRounds {},
+    GetQuadraticRound {
+        round_id: u64,
+    },
+    ListQuadraticRounds {},
+    ListContributions {
+        round_id: u64,
+        application: u32,
+    },
+    QuadraticMatching {
+        round_id: u64,
+    },
+    RecurringSchedule {
+        proposal_id: u64,
+    },
+    Config {},
+    GovernanceToken {},
+    MembershipNft {
+        address: String,
+    },
+    /// Completion certificates of the grants paid to `recipient`
+    ListCertificates {
+        recipient: String,
+    },
+    /// CW20 deposits held by the treasury, per accepted contract
+    Cw20Balances {},
+    /// Unstaked tokens still unbonding or waiting to be claimed, returns cw_controllers::ClaimsResponse
+    Claims {
+        address: String,
+    },
+    GetApplication {
+        application_id: u64,
+    },
+    ListApplications {},
+    ListReviewers {},
+    ListReviews {
+        proposal_id: u64,
+    },
+    GetDelinquency {
+        address: String,
+    },
+    ListDelinquents {},
+    ListAllowedRecipients {},
+    ListBlockedRecipients {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MembershipNftResponse {
+    pub class_id: Option<String>,
+    pub nft: Option<MembershipNft>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CertificateListResponse {
+    pub class_id: Option<String>,
+    pub certificates: Vec<GrantCertificate>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20BalancesResponse {
+    pub balances: Vec<Cw20Coin>, // `address` is the CW20 contract
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GovernanceTokenResponse {
+    pub denom: Option<String>,
+}
+
+/// cw3's `ProposalListResponse` with the Coreum messages proposals can carry
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalListResponse {
+    pub proposals: Vec<ProposalResponse<CoreumMsg>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MatchingResponse {
+    pub application: u32,
+    pub contributed: Uint128,
+    pub matched: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RecurringResponse {
+    pub period_amount: Uint128,
+    pub interval: u64,
+    pub next_due: Option<u64>, // UNIX timestamp, None before execution or once nothing remains
+    pub periods_remaining: u32,
+    pub periods_due: u32, // due but not yet claimed
 }
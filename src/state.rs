@@ -1,19 +1,349 @@
-use cw_storage_plus::{Item, Map};
+use cw_controllers::Claims;
+use cw_storage_plus::{Item, Map, SnapshotItem, SnapshotMap, Strategy};
 use serde::{Deserialize, Serialize};
-use cosmwasm_std::{Addr, Storage, Uint128};
+use coreum_wasm_sdk::core::CoreumMsg;
+use cosmwasm_std::{Addr, Coin, CosmosMsg, Decimal, Uint128};
+use cw3::Vote;
+use crate::msg::{FundingApplicationInit, MemberInit, RoundApplicationInit};
 use schemars::JsonSchema;
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Anti-spam fee in the grant denom that non-members pay to apply
+    pub application_fee: Uint128,
+    /// Seconds an application waits for a sponsor before it expires
+    pub application_period: u64,
+    /// Whether the fee of an expired application is burned rather than returned to the applicant
+    pub burn_expired_fees: bool,
+    /// Reviews a proposal needs before voting opens, 0 opens voting straight away
+    pub min_reviews: u32,
+    /// Only allow-listed addresses can receive grants when set
+    pub allowlist_enabled: bool,
+    /// Can manage the recipient allow-list and blocklist besides the DAO itself
+    pub compliance: Option<Addr>,
+    pub content_limits: ContentLimits,
+    /// Can pause payouts in an emergency without waiting for a vote
+    pub guardian: Option<Addr>,
+    pub paused_until: Option<u64>, // UNIX timestamp
+    /// Seconds a passed proposal waits after its voting period before it can be executed
+    pub timelock: u64,
+    /// Share of the total weight at a proposal's start that has to cast a ballot for its vote to count
+    pub quorum: Decimal,
+    /// Can veto passed proposals during the timelock
+    pub veto_council: Option<VetoCouncil>,
+    /// External cw4 group holding the membership, the built-in member list is used when unset
+    pub group: Option<Addr>,
+    /// AssetFT denom whose staked balance is the voting weight
+    pub governance_token: Option<String>,
+    pub staking_mode: StakingMode,
+    /// Seconds unstaked tokens wait before they can be claimed
+    pub unbonding_period: u64,
+    /// AssetNFT class of the soulbound membership NFTs
+    pub membership_nft_class: Option<String>,
+    /// AssetNFT class of the grant completion certificates
+    pub certificate_class: Option<String>,
+    /// Denom treasury, grants and fees are held in, a native denom or an AssetFT smart token
+    pub grant_denom: String,
+    /// CW20 contracts whose tokens the treasury accepts and grants can pay
+    pub cw20_tokens: Vec<Addr>,
+}
+
+/// A membership NFT, also stored as JSON in the NFT's data when it is minted. The DAO keeps the
+/// weight up to date when the member is re-weighted.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MembershipNft {
+    pub id: String,
+    pub role: String, // founder or member
+    pub weight: Uint128,
+}
+
+/// Proof that a grant was paid in full, also stored as JSON in the certificate NFT's data
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GrantCertificate {
+    pub id: String,
+    pub proposal_id: u64,
+    pub recipient: Addr,
+    pub amount: Uint128,
+    pub completed_at: u64, // UNIX timestamp
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StakingMode {
+    /// The stake is the whole weight, anyone with a stake is a member
+    #[default]
+    Replace,
+    /// Members add their stake to their fixed weight, only members can stake
+    Supplement,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VetoCouncil {
+    Addresses(Vec<Addr>),
+    /// Every member of this cw4 group contract sits on the council
+    Group(Addr),
+}
+
+/// A veto council as given in messages, before its addresses are validated
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VetoCouncilInit {
+    Addresses(Vec<String>),
+    Group(String),
+}
+
+/// Bounds on the text stored with every proposal, lengths are counted in characters
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContentLimits {
+    pub min_title_length: u32,
+    pub max_title_length: u32,
+    pub min_description_length: u32,
+    pub max_description_length: u32,
+    pub max_url_length: u32,
+}
+
+impl Default for ContentLimits {
+    fn default() -> Self {
+        ContentLimits {
+            min_title_length: 1,
+            max_title_length: 128,
+            min_description_length: 1,
+            max_description_length: 2048,
+            max_url_length: 256,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Proposal {
     pub id: u64,
+    pub proposer: Addr, // the member who proposed it, or sponsored the application it came from
     pub title: String,
     pub description: String,
+    pub url: Option<String>, // off-chain details
+    pub content_hash: Option<String>, // hex sha256 of the full proposal document, e.g. on IPFS
     pub votes_for: Uint128,
-    pub votes_against: Uint128,
+    pub votes_against: Uint128, // includes veto votes
+    pub votes_abstain: Uint128,
+    pub start_height: u64, // ballots are weighed with the membership at this block
     pub executed: bool,
     pub amount: Uint128,
     pub recipient: Addr,
     pub voting_end: u64, // UNIX timestamp
+    pub review_end: u64, // UNIX timestamp, reviews are due by then while the proposal is in review
+    pub msgs: Vec<CosmosMsg<CoreumMsg>>, // dispatched by the DAO on execution, after any grant payout
+    pub payout: Payout,
+    pub reserved: Uint128, // what `RESERVED` still holds for the grant, fees included
+    pub action: Option<ProposalAction>, // executed instead of a payout when set
+    pub status: ProposalStatus,
+    pub reviewers: Vec<Addr>,
+    pub review_count: u32,
+    pub review_score: u32, // sum of all review scores
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatus {
+    /// Waiting for enough reviews before members can vote
+    InReview,
+    Open,
+    /// The DAO took back the undistributed part of the grant
+    ClawedBack,
+    /// Stopped by the veto council during the timelock, it can no longer be executed
+    Vetoed {
+        by: Addr,
+        reason: String,
+    },
+    /// Closed after its vote failed
+    Rejected,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Ballot {
+    pub vote: Vote,
+    pub weight: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Review {
+    pub reviewer: Addr,
+    pub score: u8,
+    pub comment_hash: String, // hash of the review text stored off-chain
+}
+
+/// Governance actions a proposal can carry out when it passes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalAction {
+    /// Pays out what has vested on a streaming grant and returns the rest to the treasury
+    TerminateStream {
+        proposal_id: u64,
+    },
+    /// Pays the periods already due on a recurring grant and returns the rest to the treasury
+    StopRecurring {
+        proposal_id: u64,
+    },
+    /// Grants or revokes the reviewer role
+    UpdateReviewers {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Returns the undistributed part of an escrowed grant and marks the grantee delinquent
+    Clawback {
+        proposal_id: u64,
+        reason: String,
+    },
+    UpdateRecipientLists {
+        update: RecipientListUpdate,
+    },
+    SetAllowlistMode {
+        enabled: bool,
+    },
+    /// Appoints or removes the compliance role
+    SetCompliance {
+        address: Option<String>,
+    },
+    SetContentLimits {
+        limits: ContentLimits,
+    },
+    /// Appoints, replaces or removes the guardian, lifting any pause in place
+    SetGuardian {
+        address: Option<String>,
+    },
+    /// Rotates the veto council, or removes the veto power when empty
+    SetVetoCouncil {
+        council: Option<VetoCouncilInit>,
+    },
+    /// Adds members or changes their weight, then removes members. Only for the built-in member list.
+    UpdateMembers {
+        add: Vec<MemberInit>,
+        remove: Vec<String>,
+    },
+    /// Accepts or stops accepting CW20 contracts for deposits and new grants
+    UpdateCw20Tokens {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Administers an AssetFT smart token the DAO issued
+    AdministerToken {
+        msg: TokenAdminMsg,
+    },
+    /// Opens a grant round, its budget is reserved until the round is finalized
+    CreateRound {
+        title: String,
+        description: String,
+        method: TallyMethod,
+        budget: Uint128,
+        applications: Vec<RoundApplicationInit>,
+    },
+    /// Opens a quadratic funding round, its matching pool is reserved until the round is finalized
+    CreateQuadraticRound {
+        title: String,
+        description: String,
+        matching_pool: Uint128,
+        contribution_cap: Option<Uint128>,
+        applications: Vec<FundingApplicationInit>,
+    },
+}
+
+/// Admin operations on an AssetFT smart token, executed as the matching `assetft::Msg`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenAdminMsg {
+    /// Mints into the treasury
+    Mint { coin: Coin },
+    /// Burns from the treasury
+    Burn { coin: Coin },
+    Freeze { account: String, coin: Coin },
+    Unfreeze { account: String, coin: Coin },
+    GloballyFreeze { denom: String },
+    GloballyUnfreeze { denom: String },
+    /// Sets how much of the token `account` may hold
+    SetWhitelistedLimit { account: String, coin: Coin },
+}
+
+impl TokenAdminMsg {
+    pub fn denom(&self) -> &str {
+        match self {
+            TokenAdminMsg::Mint { coin }
+            | TokenAdminMsg::Burn { coin }
+            | TokenAdminMsg::Freeze { coin, .. }
+            | TokenAdminMsg::Unfreeze { coin, .. }
+            | TokenAdminMsg::SetWhitelistedLimit { coin, .. } => &coin.denom,
+            TokenAdminMsg::GloballyFreeze { denom } | TokenAdminMsg::GloballyUnfreeze { denom } => denom,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct RecipientListUpdate {
+    pub allow: Vec<String>,
+    pub disallow: Vec<String>,
+    pub block: Vec<String>,
+    pub unblock: Vec<String>,
+}
+
+/// Why a grantee is barred from receiving further grants
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Delinquency {
+    pub address: Addr,
+    pub proposal_id: u64,
+    pub reason: String,
+}
+
+/// How an executed proposal pays its recipient
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Payout {
+    /// The whole amount is sent on execution
+    LumpSum,
+    /// Execution reserves the amount, each milestone is released once its deliverable is approved
+    Milestones {
+        reviewer: Option<Addr>,
+        milestones: Vec<Milestone>,
+    },
+    /// Execution reserves the amount, which then unlocks linearly between start and end
+    Vesting {
+        start: u64, // UNIX timestamp
+        end: u64, // UNIX timestamp
+        cliff: Option<u64>, // nothing can be claimed before this UNIX timestamp
+        claimed: Uint128,
+        terminated_at: Option<u64>, // vesting stops here when the DAO ends the stream
+    },
+    /// Execution reserves every period, the grantee claims each one as it falls due
+    Recurring {
+        period_amount: Uint128,
+        interval: u64, // seconds between periods
+        periods: u32,
+        start: Option<u64>, // UNIX timestamp of execution, the first period is due one interval later
+        claimed: u32,
+        stopped: bool,
+    },
+    /// The whole amount is sent on execution, in a CW20 token instead of the grant denom
+    Cw20 {
+        contract: Addr,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MilestoneStatus {
+    Pending,
+    Submitted,
+    Approved,
+    Released,
+    Cancelled,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Milestone {
+    pub description: String,
+    pub amount: Uint128,
+    pub deliverable: Option<String>, // link submitted by the grantee
+    pub approvals: Vec<Addr>,
+    pub submitted_height: Option<u64>, // approvals are weighed with the members at this block
+    pub status: MilestoneStatus,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -22,8 +352,136 @@ pub struct Member {
     pub weight: Uint128
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApplicationStatus {
+    Pending,
+    Sponsored { proposal_id: u64 },
+    Expired,
+}
+
+/// A grant request from someone outside the DAO, waiting for a member to sponsor it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GrantApplication {
+    pub id: u64,
+    pub applicant: Addr,
+    pub title: String,
+    pub description: String,
+    pub requested: Uint128,
+    pub recipient: Addr,
+    pub fee: Uint128,
+    pub reserved: Uint128, // the fee with what refunding it costs, held while the application is pending
+    pub expires: u64, // UNIX timestamp
+    pub status: ApplicationStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TallyMethod {
+    /// Every application a ballot lists receives the voter's full weight
+    Approval,
+    /// Ballots rank applications, winners are found by instant-runoff
+    RankedChoice,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoundApplication {
+    pub title: String,
+    pub description: String,
+    pub recipient: Addr,
+    pub amount: Uint128,
+}
+
+/// A grant round groups competing applications for one budget
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GrantRound {
+    pub id: u64,
+    pub title: String,
+    pub description: String,
+    pub method: TallyMethod,
+    pub budget: Uint128,
+    pub reserved: Uint128, // the budget with what paying it out costs, held until the round is finalized
+    pub applications: Vec<RoundApplication>,
+    pub start_height: u64, // ballots are weighed with the members at this block
+    pub voting_end: u64, // UNIX timestamp
+    pub finalized: bool,
+    pub winners: Vec<u32>, // indexes into applications
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoundBallot {
+    pub choices: Vec<u32>, // indexes into applications, most preferred first
+    pub weight: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FundingApplication {
+    pub title: String,
+    pub description: String,
+    pub recipient: Addr,
+}
+
+/// A quadratic funding round: donations to applications are matched from a DAO funded pool
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QuadraticRound {
+    pub id: u64,
+    pub title: String,
+    pub description: String,
+    pub matching_pool: Uint128,
+    /// Only this much of one contributor's donations to an application counts towards matching
+    pub contribution_cap: Option<Uint128>,
+    pub applications: Vec<FundingApplication>,
+    pub contributed: Vec<Uint128>, // per application
+    pub matched: Vec<Uint128>, // per application, set when the round is finalized
+    pub reserved: Uint128, // the pool and contributions with what paying them out costs
+    pub round_end: u64, // UNIX timestamp
+    pub finalized: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Contribution {
+    pub contributor: Addr,
+    pub amount: Uint128,
+}
+
 
 pub const STATE: Item<()> = Item::new("state");
+pub const CONFIG: Item<Config> = Item::new("config");
 pub const PROPOSALS: Map<&str, Proposal> = Map::new("proposals");
 pub const PROPOSAL_COUNT: Item<u64> = Item::new("proposal_count");
-pub const MEMBERS: Map<&str, Member> = Map::new("members");
\ No newline at end of file
+// The built-in member list, snapshotted every block so ballots use the weight at the proposal's start
+pub const MEMBERS: SnapshotMap<&str, Member> =
+    SnapshotMap::new("members", "members__checkpoints", "members__changelog", Strategy::EveryBlock);
+// Sum of all member weights, kept in sync with an external group through its member changed hook
+pub const TOTAL_WEIGHT: SnapshotItem<Uint128> =
+    SnapshotItem::new("total_weight", "total_weight__checkpoints", "total_weight__changelog", Strategy::EveryBlock);
+// Staked governance tokens, snapshotted every block so ballots use the stake at the proposal's start
+pub const STAKES: SnapshotMap<&str, Uint128> =
+    SnapshotMap::new("stakes", "stakes__checkpoints", "stakes__changelog", Strategy::EveryBlock);
+// Unstaked tokens in their unbonding period
+pub const CLAIMS: Claims = Claims::new("claims");
+// The membership NFT each member currently holds
+pub const MEMBERSHIP_NFTS: Map<&str, MembershipNft> = Map::new("membership_nfts");
+pub const MEMBERSHIP_NFT_COUNT: Item<u64> = Item::new("membership_nft_count");
+// Keyed by recipient and proposal id
+pub const CERTIFICATES: Map<(&str, &str), GrantCertificate> = Map::new("certificates");
+// CW20 deposits not yet paid out, keyed by CW20 contract
+pub const CW20_BALANCES: Map<&str, Uint128> = Map::new("cw20_balances");
+// (proposal id, voter) -> ballot
+pub const BALLOTS: Map<(&str, &str), Ballot> = Map::new("ballots");
+pub const ROUNDS: Map<&str, GrantRound> = Map::new("rounds");
+pub const ROUND_COUNT: Item<u64> = Item::new("round_count");
+pub const ROUND_BALLOTS: Map<(&str, &str), RoundBallot> = Map::new("round_ballots");
+pub const QUADRATIC_ROUNDS: Map<&str, QuadraticRound> = Map::new("quadratic_rounds");
+pub const QUADRATIC_ROUND_COUNT: Item<u64> = Item::new("quadratic_round_count");
+// (round id, application index, contributor) -> total donated
+pub const CONTRIBUTIONS: Map<(&str, &str, &str), Uint128> = Map::new("contributions");
+// Funds held in the contract that are not available to new grants
+pub const RESERVED: Item<Uint128> = Item::new("reserved");
+pub const APPLICATIONS: Map<&str, GrantApplication> = Map::new("applications");
+pub const APPLICATION_COUNT: Item<u64> = Item::new("application_count");
+pub const REVIEWERS: Map<&str, Addr> = Map::new("reviewers");
+pub const REVIEWS: Map<(&str, &str), Review> = Map::new("reviews");
+pub const DELINQUENTS: Map<&str, Delinquency> = Map::new("delinquents");
+pub const RECIPIENT_ALLOWLIST: Map<&str, Addr> = Map::new("recipient_allowlist");
+pub const RECIPIENT_BLOCKLIST: Map<&str, Addr> = Map::new("recipient_blocklist");